@@ -0,0 +1,21 @@
+use std::path::Path;
+
+fn program(day: &str) -> Vec<i64> {
+  let path = format!("../day/{}/input.csv", day);
+  println!("cargo:rerun-if-changed={}", path);
+  helpers::loader::load_integer_row_list(&path)[0].clone()
+}
+
+fn main() {
+  let out_dir = std::env::var("OUT_DIR").unwrap();
+  let out_dir = Path::new(&out_dir);
+  let mut day02 = program("02");
+  day02[1] = 12;
+  day02[2] = 2;
+  icc::translate::translate_to_file("day02", &day02, out_dir.join("day02.rs")).unwrap();
+  for day in &["05", "07", "09", "11"] {
+    let name = format!("day{}", day);
+    icc::translate::translate_to_file(&name, &program(day), out_dir.join(name.clone() + ".rs"))
+      .unwrap();
+  }
+}
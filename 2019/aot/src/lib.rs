@@ -0,0 +1,147 @@
+include!(concat!(env!("OUT_DIR"), "/day02.rs"));
+include!(concat!(env!("OUT_DIR"), "/day05.rs"));
+include!(concat!(env!("OUT_DIR"), "/day07.rs"));
+include!(concat!(env!("OUT_DIR"), "/day09.rs"));
+include!(concat!(env!("OUT_DIR"), "/day11.rs"));
+
+#[cfg(test)]
+mod tests {
+  use helpers::loader::load_integer_row_list;
+  use icc::state::State;
+  use icc::translate::Frame;
+  use icc::Computer;
+  use std::collections::HashMap;
+
+  fn computer(day: &str) -> Computer {
+    Computer::load(
+      0,
+      &load_integer_row_list(&format!("../day/{}/input.csv", day))[0],
+    )
+  }
+
+  #[test]
+  fn day02() {
+    let mut comp = computer("02");
+    comp.edit_memory(1, 12);
+    comp.edit_memory(2, 2);
+    let expected = icc::run_to_end(&mut comp, &[]);
+    let mut frame = super::day02::load(0);
+    let actual = super::day02::run_to_end(&mut frame, &[]);
+    assert_eq!(actual, expected);
+    assert_eq!(frame.read_memory(0), comp.read_memory(0));
+    assert_eq!(frame.read_memory(0), Some(6_627_023));
+  }
+  #[test]
+  fn day05() {
+    for input in &[1, 5] {
+      let expected = icc::run_to_end(&mut computer("05"), &[*input]);
+      let mut frame = super::day05::load(0);
+      let actual = super::day05::run_to_end(&mut frame, &[*input]);
+      assert_eq!(actual, expected);
+      // patches the instruction at 6 before running it
+      assert!(frame.is_interpreted());
+    }
+  }
+  #[test]
+  fn day07_single() {
+    for phase in 0..5 {
+      let expected = icc::run_to_end(&mut computer("07"), &[phase, 17]);
+      let mut frame = super::day07::load(0);
+      let actual = super::day07::run_to_end(&mut frame, &[phase, 17]);
+      assert_eq!(actual, expected);
+    }
+  }
+  #[test]
+  fn day07_feedback() {
+    let settings = [9, 8, 7, 6, 5];
+    let mut frames: Vec<Frame> = (0..settings.len()).map(super::day07::load).collect();
+    let mut amps = icc::adama::Amplifiers::new(&super::day07::PROGRAM, &settings);
+    let expected = amps.run_to_end(Some(0));
+    let mut buffers: Vec<Vec<i64>> = settings.iter().map(|phase| vec![*phase]).collect();
+    buffers[0].push(0);
+    let mut last = None;
+    let mut halted = 0;
+    let mut idx = 0;
+    while halted < frames.len() {
+      match super::day07::run(&mut frames[idx]) {
+        State::Output(_, value) => {
+          last = Some(value);
+          buffers[(idx + 1) % frames.len()].push(value);
+        }
+        State::Input if !buffers[idx].is_empty() => {
+          let value = buffers[idx].remove(0);
+          assert!(frames[idx].input_value(value));
+        }
+        State::Input => idx = (idx + 1) % frames.len(),
+        State::Halted => {
+          halted += 1;
+          idx = (idx + 1) % frames.len();
+        }
+        state => panic!("unexpected {:?}", state),
+      }
+    }
+    assert_eq!(last, Some(expected));
+  }
+  #[test]
+  fn day09() {
+    for input in &[1, 2] {
+      let expected = icc::run_to_end(&mut computer("09"), &[*input]);
+      let mut frame = super::day09::load(0);
+      let actual = super::day09::run_to_end(&mut frame, &[*input]);
+      assert_eq!(actual, expected);
+      assert!(!frame.is_interpreted());
+    }
+  }
+
+  fn paint<F>(mut run: F) -> HashMap<(i64, i64), i64>
+  where
+    F: FnMut(Option<i64>) -> State,
+  {
+    let mut panel = HashMap::new();
+    let (mut x, mut y, mut dx, mut dy) = (0, 0, 0, 1);
+    let mut outputs = Vec::new();
+    let mut input = None;
+    loop {
+      match run(input.take()) {
+        State::Halted => break,
+        State::Input => input = Some(*panel.get(&(x, y)).unwrap_or(&0)),
+        State::Output(_, value) => {
+          outputs.push(value);
+          if outputs.len() == 2 {
+            panel.insert((x, y), outputs[0]);
+            let (ndx, ndy) = if outputs[1] == 0 {
+              (-dy, dx)
+            } else {
+              (dy, -dx)
+            };
+            dx = ndx;
+            dy = ndy;
+            x += dx;
+            y += dy;
+            outputs.clear();
+          }
+        }
+        state => panic!("unexpected {:?}", state),
+      }
+    }
+    panel
+  }
+  #[test]
+  fn day11() {
+    let mut comp = computer("11");
+    let expected = paint(|input| {
+      if let Some(value) = input {
+        assert!(comp.input_value(value));
+      }
+      comp.run()
+    });
+    let mut frame = super::day11::load(0);
+    let actual = paint(|input| {
+      if let Some(value) = input {
+        assert!(frame.input_value(value));
+      }
+      super::day11::run(&mut frame)
+    });
+    assert_eq!(actual, expected);
+  }
+}
@@ -1,15 +1,12 @@
-pub fn run() -> String {
-  format!(
-    "{}",
-    helpers::loader::load_integer_list("./day/01/input.csv").fuel_need()
-  )
+pub fn solve(modules: &[i64]) -> i64 {
+  modules.fuel_need()
 }
 
 trait FuelNeed {
   fn fuel_need(&self) -> i64;
 }
 
-impl FuelNeed for Vec<i64> {
+impl FuelNeed for [i64] {
   fn fuel_need(&self) -> i64 {
     self.iter().fold(0, |s, v| s + fuel_requirement(*v))
   }
@@ -36,10 +33,14 @@ fn weight_fuel_requirement(module_weight: i64) -> i64 {
 #[cfg(test)]
 mod tests {
   use super::{fuel_requirement, FuelNeed};
+  use helpers::input::puzzle_input;
+  use helpers::solver::parse_lines;
   #[test]
   fn truth() {
     assert_eq!(
-      loader::load_integer_list("./day/01/input.csv").fuel_need(),
+      parse_lines::<i64>(&puzzle_input("day/01/input.csv"))
+        .unwrap()
+        .fuel_need(),
       5_183_653
     );
   }
@@ -1,6 +1,18 @@
 pub mod a;
 pub mod b;
-use helpers::Solvers;
-pub fn solve() -> Solvers {
-  Solvers::new(a::run, b::run)
+use helpers::solver::{parse_lines, ParseError, SolveError, Solver};
+use std::fmt::Display;
+
+pub struct Day01;
+impl Solver for Day01 {
+  type Input = Vec<i64>;
+  fn parse(&self, text: &str) -> Result<Vec<i64>, ParseError> {
+    parse_lines(text)
+  }
+  fn part_a(&self, input: &Vec<i64>) -> Result<impl Display, SolveError> {
+    Ok(a::solve(input))
+  }
+  fn part_b(&self, input: &Vec<i64>) -> Result<impl Display, SolveError> {
+    Ok(b::solve(input))
+  }
 }
@@ -1,20 +1,19 @@
-pub fn run() -> String {
-  let comp = icc::Computer::load(
-    0,
-    &helpers::loader::load_integer_row_list("./day/02/input.csv")[0],
-  );
-  let result = icc::input::run(&comp, Some(icc::input::Input::new(12, 2)));
-
-  format!("{}", result)
+pub fn solve(program: &[i64]) -> i64 {
+  let comp = icc::Computer::load(0, program);
+  icc::input::run(&comp, Some(icc::input::Input::new(12, 2)))
 }
 
 #[cfg(test)]
 mod tests {
-  use helpers::loader::load_integer_row_list;
+  use helpers::input::puzzle_input;
+  use helpers::solver::parse_program;
   use icc::Computer;
   #[test]
   fn truth() {
-    let comp = Computer::load(0, &load_integer_row_list("../day/02/input.csv")[0]);
+    let comp = Computer::load(
+      0,
+      &parse_program(&puzzle_input("day/02/input.csv")).unwrap(),
+    );
     let actual = icc::input::run(&comp, Some(icc::input::Input::new(12, 2)));
 
     assert_eq!(actual, 6_627_023);
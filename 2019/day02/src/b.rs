@@ -1,21 +1,22 @@
-pub fn run() -> String {
-  let comp = icc::Computer::load(
-    0,
-    &helpers::loader::load_integer_row_list("./day/02/input.csv")[0],
-  );
-  let result = icc::input::find_cent(&comp, 19_690_720).expect("should give some value");
-  format!("{}", result)
+pub fn solve(program: &[i64]) -> Option<i64> {
+  let comp = icc::Computer::load(0, program);
+  icc::input::solve_cent(&comp, 19_690_720)
 }
 #[cfg(test)]
 mod tests {
-  use helpers::loader::load_integer_row_list;
-  use icc::input::find_cent;
+  use helpers::input::puzzle_input;
+  use helpers::solver::parse_program;
+  use icc::input::{find_cent, solve_cent};
   use icc::Computer;
   #[test]
   fn truth() {
-    let mut comp = Computer::load(0, &load_integer_row_list("../day/02/input.csv")[0]);
+    let mut comp = Computer::load(
+      0,
+      &parse_program(&puzzle_input("day/02/input.csv")).unwrap(),
+    );
     let actual = find_cent(&mut comp, 19_690_720).expect("should give some value");
 
     assert_eq!(actual, 4019);
+    assert_eq!(solve_cent(&comp, 19_690_720), Some(actual));
   }
 }
@@ -1,3 +1,3 @@
-pub fn run() -> String {
-  format!("{}", wire::solve_day03a())
+pub fn solve(wires: &[Vec<wire::Move>]) -> Option<i64> {
+  wire::shortest_distance(wires.to_vec())
 }
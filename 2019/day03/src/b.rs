@@ -1,5 +1,5 @@
-pub fn run() -> String {
-  format!("{}", wire::solve_day03b())
+pub fn solve(wires: &[Vec<wire::Move>]) -> Option<i64> {
+  wire::fewest_steps(wires.to_vec())
 }
 #[cfg(test)]
 mod tests {
@@ -1,7 +1,35 @@
 pub mod a;
 pub mod b;
 
-use helpers::Solvers;
-pub fn solve() -> Solvers {
-  Solvers::new(a::run, b::run)
+use helpers::solver::{ParseError, SolveError, Solver};
+use std::fmt::Display;
+use wire::Move;
+
+pub struct Day03;
+impl Solver for Day03 {
+  type Input = Vec<Vec<Move>>;
+  fn parse(&self, text: &str) -> Result<Vec<Vec<Move>>, ParseError> {
+    let wires = text
+      .lines()
+      .enumerate()
+      .filter(|(_, line)| !line.trim().is_empty())
+      .map(|(idx, line)| {
+        Move::many_from_string(line.trim())
+          .map_err(|err| ParseError::Line(idx + 1, format!("{}", err)))
+      })
+      .collect::<Result<Vec<Vec<Move>>, ParseError>>()?;
+    if wires.len() != 2 {
+      return Err(ParseError::Format(format!(
+        "expected two wires, found {}",
+        wires.len()
+      )));
+    }
+    Ok(wires)
+  }
+  fn part_a(&self, input: &Vec<Vec<Move>>) -> Result<impl Display, SolveError> {
+    a::solve(input).ok_or(SolveError::NoSolution)
+  }
+  fn part_b(&self, input: &Vec<Vec<Move>>) -> Result<impl Display, SolveError> {
+    b::solve(input).ok_or(SolveError::NoSolution)
+  }
 }
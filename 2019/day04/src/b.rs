@@ -1,10 +1,8 @@
-pub fn run() -> String {
-  format!("{}", naive_count())
+pub fn solve(bounds: (u32, u32)) -> u32 {
+  naive_count(bounds)
 }
 
 const PASSWORD_LENGTH: usize = 6;
-const PASSWORD_MIN: u32 = 125_730;
-const PASSWORD_MAX: u32 = 579_381;
 #[derive(PartialEq, Eq, Debug)]
 enum Result {
   Low,
@@ -13,11 +11,11 @@ enum Result {
   Bad,
 }
 
-fn naive_count() -> u32 {
-  let mut password = [1, 2, 5, 7, 3, 0];
+fn naive_count(bounds: (u32, u32)) -> u32 {
+  let mut password = password_digits(bounds.0);
   let mut possible = 0;
   loop {
-    match is_valid(password) {
+    match is_valid(password, bounds) {
       Result::Good => possible += 1,
       Result::High => break,
       _ => (),
@@ -44,7 +42,7 @@ fn increase(password: &mut [u8; PASSWORD_LENGTH]) {
   password[1] %= 10;
 }
 
-fn is_valid(password: [u8; PASSWORD_LENGTH]) -> Result {
+fn is_valid(password: [u8; PASSWORD_LENGTH], (min, max): (u32, u32)) -> Result {
   let mut has_double = false;
   let mut streak = 1;
   for idx in 1..PASSWORD_LENGTH {
@@ -60,10 +58,10 @@ fn is_valid(password: [u8; PASSWORD_LENGTH]) -> Result {
     }
   }
   let value = password_value(password);
-  if value <= PASSWORD_MIN {
+  if value <= min {
     return Result::Low;
   }
-  if value >= PASSWORD_MAX {
+  if value >= max {
     return Result::High;
   }
   if streak == 2 {
@@ -84,13 +82,22 @@ fn password_value(given: [u8; PASSWORD_LENGTH]) -> u32 {
     .sum()
 }
 
+fn password_digits(value: u32) -> [u8; PASSWORD_LENGTH] {
+  let mut password = [0; PASSWORD_LENGTH];
+  for (idx, digit) in password.iter_mut().rev().enumerate() {
+    *digit = (value / 10u32.pow(idx as u32) % 10) as u8;
+  }
+  password
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
+  const BOUNDS: (u32, u32) = (125_730, 579_381);
   #[ignore]
   #[test]
   fn truth() {
-    assert_eq!(naive_count(), 2081);
+    assert_eq!(naive_count(BOUNDS), 2081);
   }
   #[test]
   fn increase_1() {
@@ -114,46 +121,46 @@ mod tests {
   }
   #[test]
   fn valid_1() {
-    assert_eq!(is_valid([2, 2, 3, 3, 4, 4]), Result::Good);
+    assert_eq!(is_valid([2, 2, 3, 3, 4, 4], BOUNDS), Result::Good);
   }
   #[test]
   fn valid_2() {
-    assert_eq!(is_valid([2, 3, 3, 4, 6, 7]), Result::Good);
+    assert_eq!(is_valid([2, 3, 3, 4, 6, 7], BOUNDS), Result::Good);
   }
   #[test]
   fn valid_3() {
-    assert_eq!(is_valid([2, 2, 2, 2, 3, 3]), Result::Good);
+    assert_eq!(is_valid([2, 2, 2, 2, 3, 3], BOUNDS), Result::Good);
   }
   #[test]
   fn valid_4() {
-    assert_eq!(is_valid([2, 2, 3, 4, 4, 4]), Result::Good);
+    assert_eq!(is_valid([2, 2, 3, 4, 4, 4], BOUNDS), Result::Good);
   }
   #[test]
   fn valid_5() {
-    assert_eq!(is_valid([2, 2, 2, 3, 3, 4]), Result::Good);
+    assert_eq!(is_valid([2, 2, 2, 3, 3, 4], BOUNDS), Result::Good);
   }
   #[test]
   fn invalid_1() {
-    assert_eq!(is_valid([2, 2, 3, 4, 5, 0]), Result::Bad);
+    assert_eq!(is_valid([2, 2, 3, 4, 5, 0], BOUNDS), Result::Bad);
   }
   #[test]
   fn invalid_2() {
-    assert_eq!(is_valid([2, 3, 4, 6, 7, 9]), Result::Bad);
+    assert_eq!(is_valid([2, 3, 4, 6, 7, 9], BOUNDS), Result::Bad);
   }
   #[test]
   fn invalid_3() {
-    assert_eq!(is_valid([1, 1, 1, 1, 1, 1]), Result::Low);
+    assert_eq!(is_valid([1, 1, 1, 1, 1, 1], BOUNDS), Result::Low);
   }
   #[test]
   fn invalid_4() {
-    assert_eq!(is_valid([6, 6, 6, 6, 6, 6]), Result::High);
+    assert_eq!(is_valid([6, 6, 6, 6, 6, 6], BOUNDS), Result::High);
   }
   #[test]
   fn invalid_5() {
-    assert_eq!(is_valid([2, 2, 2, 3, 3, 3]), Result::Bad);
+    assert_eq!(is_valid([2, 2, 2, 3, 3, 3], BOUNDS), Result::Bad);
   }
   #[test]
   fn invalid_7() {
-    assert_eq!(is_valid([3, 3, 3, 3, 3, 3]), Result::Bad);
+    assert_eq!(is_valid([3, 3, 3, 3, 3, 3], BOUNDS), Result::Bad);
   }
 }
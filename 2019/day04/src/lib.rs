@@ -1,7 +1,33 @@
 pub mod a;
 pub mod b;
 
-use helpers::Solvers;
-pub fn solve() -> Solvers {
-  Solvers::new(a::run, b::run)
+use helpers::solver::{ParseError, SolveError, Solver};
+use std::fmt::Display;
+
+pub struct Day04;
+impl Solver for Day04 {
+  type Input = (u32, u32);
+  fn parse(&self, text: &str) -> Result<(u32, u32), ParseError> {
+    let bound = |value: &str| {
+      value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|v| (100_000..1_000_000).contains(v))
+        .ok_or_else(|| ParseError::Format(format!("'{}' is not a six digit number", value.trim())))
+    };
+    match text.trim().split('-').collect::<Vec<&str>>().as_slice() {
+      [min, max] => Ok((bound(min)?, bound(max)?)),
+      _ => Err(ParseError::Format(format!(
+        "'{}' is not a range like 100000-999999",
+        text.trim()
+      ))),
+    }
+  }
+  fn part_a(&self, input: &(u32, u32)) -> Result<impl Display, SolveError> {
+    Ok(a::solve(*input))
+  }
+  fn part_b(&self, input: &(u32, u32)) -> Result<impl Display, SolveError> {
+    Ok(b::solve(*input))
+  }
 }
@@ -1,19 +1,29 @@
-use helpers::loader::load_integer_row_list;
+use helpers::SolveError;
 
-pub fn run() -> String {
-  let mut comp = icc::Computer::load(0, &load_integer_row_list("./day/05/input.csv")[0]);
-  let result = icc::run_to_end(&mut comp, &[1]);
-  format!("{:?}", result)
+pub fn solve(program: &[i64]) -> Result<i64, SolveError> {
+  let mut comp = icc::Computer::load(0, program);
+  let output = icc::run_to_end(&mut comp, &[1]).map_err(SolveError::Failed)?;
+  match output.split_last() {
+    Some((code, tests)) if tests.iter().all(|t| *t == 0) => Ok(*code),
+    Some(_) => Err(SolveError::Failed(format!(
+      "diagnostics failed {:?}",
+      output
+    ))),
+    None => Err(SolveError::NoSolution),
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use loader::load_integer_row_list;
+  use helpers::input::puzzle_input;
+  use helpers::solver::parse_program;
 
   #[test]
   fn truth() {
-    let mut comp = icc::Computer::load(&load_integer_row_list("../day/05/input.csv")[0]);
+    let program = parse_program(&puzzle_input("day/05/input.csv")).unwrap();
+    let mut comp = icc::Computer::load(0, &program);
     let result = icc::run_to_end(&mut comp, &[1]);
-    assert_eq!(result, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 7157989]);
+    assert_eq!(result, Ok(vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 7157989]));
+    assert_eq!(super::solve(&program), Ok(7157989));
   }
 }
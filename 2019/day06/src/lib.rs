@@ -1,27 +1,32 @@
-use helpers::Solvers;
-pub fn solve() -> Solvers {
-  Solvers::new(a::run, b::run)
-}
+use helpers::solver::{ParseError, SolveError, Solver};
+use std::fmt::Display;
 
-pub mod a {
-  pub fn run() -> String {
-    let orbits = super::Orbits::from_strings(
-      &helpers::loader::load_as("./day/06/input.csv", |l| l).collect::<Vec<String>>(),
-    );
-    format!("{}", orbits.count())
+pub struct Day06;
+impl Solver for Day06 {
+  type Input = Orbits;
+  fn parse(&self, text: &str) -> Result<Orbits, ParseError> {
+    let mut lines = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+      match line.trim() {
+        "" => (),
+        line if line.matches(')').count() == 1 => lines.push(line.to_owned()),
+        line => {
+          return Err(ParseError::Line(
+            idx + 1,
+            format!("'{}' is not an orbit", line),
+          ))
+        }
+      }
+    }
+    Ok(Orbits::from_strings(&lines))
   }
-}
-pub mod b {
-  pub fn run() -> String {
-    let orbits = super::Orbits::from_strings(
-      &helpers::loader::load_as("./day/06/input.csv", |l| l).collect::<Vec<String>>(),
-    );
-    format!(
-      "{}",
-      orbits
-        .transferes_between(&"YOU".to_owned(), &"SAN".to_owned())
-        .unwrap()
-    )
+  fn part_a(&self, input: &Orbits) -> Result<impl Display, SolveError> {
+    Ok(input.count())
+  }
+  fn part_b(&self, input: &Orbits) -> Result<impl Display, SolveError> {
+    input
+      .transferes_between("YOU", "SAN")
+      .ok_or(SolveError::NoSolution)
   }
 }
 
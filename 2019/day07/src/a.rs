@@ -1,14 +1,10 @@
-pub fn run() -> String {
-  let program = helpers::loader::load_integer_row_list("./day/07/input.csv")[0].clone();
-  let result = helpers::mathy::permutations_in_memory(vec![0, 1, 2, 3, 4])
-    .iter()
-    .map(|setting| {
-      let mut amps = icc::adama::Amplifiers::new(&program, setting);
-      amps.run_to_end(None)
-    })
-    .max()
-    .unwrap();
-  format!("{}", result)
+use helpers::SolveError;
+
+pub fn solve(program: &[i64]) -> Result<i64, SolveError> {
+  let best = icc::adama::optimize(program, &[0, 1, 2, 3, 4], icc::adama::Mode::Chain, |_| ());
+  best
+    .map(|setting| setting.signal)
+    .ok_or(SolveError::NoSolution)
 }
 
 // use std::collections::HashSet;
@@ -67,19 +63,21 @@ pub fn run() -> String {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use icc::adama::{Amplifiers, Mode, Permutations};
   #[test]
   fn permutations_1() {
-    let permutations = permutations_in_memory();
-    assert_eq!(permutations.len(), 120);
+    let permutations = Permutations::new(&[0, 1, 2, 3, 4]);
+    assert_eq!(permutations.count(), 120);
   }
   #[test]
   fn test_1() {
     let program = vec![
       3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
     ];
-    let mut amps = Amplifiers::new(&program);
-    let actual = amps.test_setting([4, 3, 2, 1, 0]);
+    let mut amps = Amplifiers::with_mode(&program, &[4, 3, 2, 1, 0], Mode::Chain);
+    let actual = amps.run_to_end(Some(0));
     assert_eq!(actual, 43210);
+    assert_eq!(solve(&program).unwrap(), 43210);
   }
   #[test]
   fn test_2() {
@@ -87,8 +85,8 @@ mod tests {
       3, 23, 3, 24, 1002, 24, 10, 24, 1002, 23, -1, 23, 101, 5, 23, 23, 1, 24, 23, 23, 4, 23, 99,
       0, 0,
     ];
-    let mut amps = Amplifiers::new(&program);
-    let actual = amps.test_setting([0, 1, 2, 3, 4]);
+    let mut amps = Amplifiers::with_mode(&program, &[0, 1, 2, 3, 4], Mode::Chain);
+    let actual = amps.run_to_end(Some(0));
     assert_eq!(actual, 54321);
   }
   #[test]
@@ -97,8 +95,8 @@ mod tests {
       3, 31, 3, 32, 1002, 32, 10, 32, 1001, 31, -2, 31, 1007, 31, 0, 33, 1002, 33, 7, 33, 1, 33,
       31, 31, 1, 32, 31, 31, 4, 31, 99, 0, 0, 0,
     ];
-    let mut amps = Amplifiers::new(&program);
-    let actual = amps.test_setting([1, 0, 4, 3, 2]);
+    let mut amps = Amplifiers::with_mode(&program, &[1, 0, 4, 3, 2], Mode::Chain);
+    let actual = amps.run_to_end(Some(0));
     assert_eq!(actual, 65210);
   }
 }
@@ -1,13 +1,13 @@
-pub fn run() -> String {
-  let program = helpers::loader::load_integer_row_list("./day/07/input.csv")[0].clone();
-  let settings = vec![5, 6, 7, 8, 9];
-  let result = helpers::mathy::permutations_in_memory(settings)
-    .iter()
-    .map(|setting| {
-      let mut amps = icc::adama::Amplifiers::new(&program, &setting);
-      amps.run_to_end(Some(0))
-    })
-    .max()
-    .unwrap();
-  format!("{}", result)
+use helpers::SolveError;
+
+pub fn solve(program: &[i64]) -> Result<i64, SolveError> {
+  let best = icc::adama::optimize(
+    program,
+    &[5, 6, 7, 8, 9],
+    icc::adama::Mode::Feedback,
+    |_| (),
+  );
+  best
+    .map(|setting| setting.signal)
+    .ok_or(SolveError::NoSolution)
 }
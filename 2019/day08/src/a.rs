@@ -1,23 +1,28 @@
-pub fn run() -> String {
-  let data =
-    helpers::loader::load_as("./day/08/input.csv", |l| l).collect::<Vec<String>>()[0].clone();
-  let image = Image::from_string(25, 6, data);
-  write_image(&image);
-  "whee!".to_owned()
+pub fn solve(image: &Image) -> Option<usize> {
+  let layer = image.layers_with_fewest(0).pop()?;
+  Some(layer.count(1) * layer.count(2))
 }
 
-fn write_image(image: &Image) {
+pub fn render(image: &Image) -> String {
   let composit = image.merge();
+  let mut rows = Vec::new();
   for ih in 0..image.height {
+    let mut row = String::new();
     for iw in 0..image.width {
       if composit[ih * image.width + iw] == 1 {
-        print!("█");
+        row.push('█');
       } else {
-        print!(" ");
+        row.push(' ');
       }
     }
-    println!();
+    rows.push(row.trim_end().to_owned());
   }
+  rows.join("\n")
+}
+
+#[cfg(test)]
+fn write_image(image: &Image) {
+  println!("{}", render(image));
 }
 
 /**
@@ -152,6 +157,7 @@ impl Image {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use helpers::input::puzzle_input;
   #[test]
   fn mini_image_1() {
     let image = Image::from_string(3, 2, "123456789012".to_owned());
@@ -216,44 +222,53 @@ mod tests {
   }
   #[test]
   fn image_1() {
-    let data = loader::load_as("../day/08/input.csv", |l| l).collect::<Vec<String>>()[0].clone();
+    let data = puzzle_input("day/08/input.csv").trim().to_owned();
     let image = Image::from_string(25, 6, data);
     assert_eq!(image.layers(), 100);
   }
   #[test]
   fn image_2() {
-    let data = loader::load_as("../day/08/input.csv", |l| l).collect::<Vec<String>>()[0].clone();
+    let data = puzzle_input("day/08/input.csv").trim().to_owned();
     let image = Image::from_string(25, 6, data);
     let layers = image.layers_with_fewest(0);
     assert_eq!(layers.len(), 1);
   }
   #[test]
   fn image_3() {
-    let data = loader::load_as("../day/08/input.csv", |l| l).collect::<Vec<String>>()[0].clone();
+    let data = puzzle_input("day/08/input.csv").trim().to_owned();
     let image = Image::from_string(25, 6, data);
     let layer = image.layers_with_fewest(0).pop().unwrap();
     assert_eq!(layer.count(1), 17);
   }
   #[test]
   fn image_4() {
-    let data = loader::load_as("../day/08/input.csv", |l| l).collect::<Vec<String>>()[0].clone();
+    let data = puzzle_input("day/08/input.csv").trim().to_owned();
     let image = Image::from_string(25, 6, data);
     let layer = image.layers_with_fewest(0).pop().unwrap();
     assert_eq!(layer.count(2), 127);
   }
   #[test]
   fn image_5() {
-    let data = loader::load_as("../day/08/input.csv", |l| l).collect::<Vec<String>>()[0].clone();
+    let data = puzzle_input("day/08/input.csv").trim().to_owned();
     let image = Image::from_string(25, 6, data);
     let layer = image.layers_with_fewest(0).pop().unwrap();
     assert_eq!(layer.count(1) * layer.count(2), 2159);
   }
   #[test]
   fn image_6() {
-    let data = loader::load_as("../day/08/input.csv", |l| l).collect::<Vec<String>>()[0].clone();
+    let data = puzzle_input("day/08/input.csv").trim().to_owned();
     let image = Image::from_string(25, 6, data);
-    println!();
-    write_image(&image);
-    assert_eq!(image.merge(), vec![]);
+    assert_eq!(
+      render(&image),
+      [
+        " ██    ██ ████ █  █ ███",
+        "█  █    █    █ █  █ █  █",
+        "█       █   █  ████ █  █",
+        "█       █  █   █  █ ███",
+        "█  █ █  █ █    █  █ █ █",
+        " ██   ██  ████ █  █ █  █",
+      ]
+      .join("\n")
+    );
   }
 }
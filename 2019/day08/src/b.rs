@@ -1,3 +1,5 @@
-pub fn run() -> String {
-  "meep".to_owned()
+use super::a::{render, Image};
+
+pub fn solve(image: &Image) -> String {
+  render(image)
 }
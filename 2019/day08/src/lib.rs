@@ -1,6 +1,34 @@
 pub mod a;
 pub mod b;
-use helpers::Solvers;
-pub fn solve() -> Solvers {
-  Solvers::new(a::run, b::run)
+use a::Image;
+use helpers::solver::{ParseError, SolveError, Solver};
+use std::fmt::Display;
+
+const WIDTH: usize = 25;
+const HEIGHT: usize = 6;
+
+pub struct Day08;
+impl Solver for Day08 {
+  type Input = Image;
+  fn parse(&self, text: &str) -> Result<Image, ParseError> {
+    let data = text.trim();
+    if let Some(bad) = data.chars().find(|c| !c.is_ascii_digit()) {
+      return Err(ParseError::Format(format!("'{}' is not a pixel", bad)));
+    }
+    if data.is_empty() || !data.len().is_multiple_of(WIDTH * HEIGHT) {
+      return Err(ParseError::Format(format!(
+        "{} pixels do not make {}x{} layers",
+        data.len(),
+        WIDTH,
+        HEIGHT
+      )));
+    }
+    Ok(Image::from_string(WIDTH, HEIGHT, data.to_owned()))
+  }
+  fn part_a(&self, input: &Image) -> Result<impl Display, SolveError> {
+    a::solve(input).ok_or(SolveError::NoSolution)
+  }
+  fn part_b(&self, input: &Image) -> Result<impl Display, SolveError> {
+    Ok(b::solve(input))
+  }
 }
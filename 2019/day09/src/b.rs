@@ -1,10 +1,11 @@
-pub fn run() -> String {
-  let program = helpers::loader::load_integer_row_list("./day/09/input.csv")[0].clone();
-  let mut computer = icc::Computer::load(0, &program);
-  let result = icc::run_to_end(&mut computer, &[2]);
-  match result {
-    Ok(data) => format! {"{:?}", data},
-    Err(error) => error,
+use helpers::SolveError;
+
+pub fn solve(program: &[i64]) -> Result<i64, SolveError> {
+  let mut computer = icc::Computer::load(0, program);
+  match icc::run_to_end(&mut computer, &[2]) {
+    Ok(data) if data.len() == 1 => Ok(data[0]),
+    Ok(data) => Err(SolveError::Failed(format!("unexpected output {:?}", data))),
+    Err(error) => Err(SolveError::Failed(error)),
   }
 }
 #[cfg(test)]
@@ -1,6 +1,18 @@
 pub mod a;
 pub mod b;
-use helpers::Solvers;
-pub fn solve() -> Solvers {
-  Solvers::new(a::run, b::run)
+use helpers::solver::{parse_program, ParseError, SolveError, Solver};
+use std::fmt::Display;
+
+pub struct Day09;
+impl Solver for Day09 {
+  type Input = Vec<i64>;
+  fn parse(&self, text: &str) -> Result<Vec<i64>, ParseError> {
+    parse_program(text)
+  }
+  fn part_a(&self, input: &Vec<i64>) -> Result<impl Display, SolveError> {
+    a::solve(input)
+  }
+  fn part_b(&self, input: &Vec<i64>) -> Result<impl Display, SolveError> {
+    b::solve(input)
+  }
 }
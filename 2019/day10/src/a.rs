@@ -1,7 +1,8 @@
-pub fn run() -> String {
-  let asteroids = Asteroid::from_file("./day/10/input.map");
-  let (asteroid, visible) = visibility(&asteroids).pop().unwrap();
-  format!("From {} we see {} asteroids.", asteroid, visible)
+pub fn solve(rows: &[String]) -> Option<isize> {
+  let asteroids = Asteroid::from_rows(rows);
+  visibility(&asteroids)
+    .pop()
+    .map(|(_asteroid, visible)| visible)
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Point {
@@ -111,10 +112,11 @@ impl Asteroid {
       .flat_map(|(y, row)| Asteroid::from_string(&row, y as isize))
       .collect()
   }
-  pub fn from_file(file_name: &str) -> Vec<Asteroid> {
-    helpers::loader::read_as_iter(file_name)
+  fn from_rows(rows: &[String]) -> Vec<Asteroid> {
+    rows
+      .iter()
       .enumerate()
-      .flat_map(|(y, row)| Asteroid::from_string(&row, y as isize))
+      .flat_map(|(y, row)| Asteroid::from_string(row, y as isize))
       .collect()
   }
 }
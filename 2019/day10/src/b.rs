@@ -1,20 +1,14 @@
 use helpers::coordinates::Polar;
 
-pub fn run() -> String {
-  let map = Asteroid::from_file("./day/10/input.map");
+pub fn solve(rows: &[String]) -> Option<isize> {
+  let map = Asteroid::from_rows(rows);
 
   let mut visible = visibility(&map);
-  let actual = visible.pop().map(|(a, _u)| a).unwrap();
+  let actual = visible.pop().map(|(a, _u)| a)?;
   let sorted = sort_clockwise_from(&actual, &map);
-  let nth = |p: usize| sorted.iter().nth(p - 1).unwrap();
   let count = 200;
-  let (asteroid, clock) = nth(count);
-  format!(
-    "From our vantage point we see number {}, the magnificent {} at {} o'clock. (not 32,9)",
-    count,
-    asteroid,
-    12.0 + 12.0 * clock
-  )
+  let (asteroid, _clock) = sorted.get(count - 1)?;
+  Some(asteroid.0 * 100 + asteroid.1)
 }
 
 fn find_by_sweep(count: usize, asteroids: &Vec<Asteroid>) -> (Asteroid, f64) {
@@ -152,10 +146,11 @@ impl Asteroid {
       .flat_map(|(y, row)| Asteroid::from_string(&row, y as isize))
       .collect()
   }
-  pub fn from_file(file_name: &str) -> Vec<Asteroid> {
-    helpers::loader::read_as_iter(file_name)
+  fn from_rows(rows: &[String]) -> Vec<Asteroid> {
+    rows
+      .iter()
       .enumerate()
-      .flat_map(|(y, row)| Asteroid::from_string(&row, y as isize))
+      .flat_map(|(y, row)| Asteroid::from_string(row, y as isize))
       .collect()
   }
 }
@@ -203,6 +198,9 @@ fn visibility(map: &[Asteroid]) -> Vec<(Asteroid, isize)> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::Day10;
+  use helpers::input::puzzle_input;
+  use helpers::Solver;
   #[test]
   fn clock_0() {
     assert_eq!(clock(&Asteroid(0, 0), &Asteroid(0, 1)), 0.5);
@@ -367,7 +365,8 @@ mod tests {
   }
   #[test]
   fn big_1() {
-    let map = Asteroid::from_file("../day/10/input.map");
+    let rows = Day10.parse(&puzzle_input("day/10/input.map")).unwrap();
+    let map = Asteroid::from_rows(&rows);
 
     let mut visible = visibility(&map);
     let actual = visible.pop().map(|(a, _u)| a).unwrap();
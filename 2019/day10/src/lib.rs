@@ -1,6 +1,27 @@
 pub mod a;
 pub mod b;
-use helpers::Solvers;
-pub fn solve() -> Solvers {
-    Solvers::new(a::run, b::run)
+use helpers::solver::{ParseError, SolveError, Solver};
+use std::fmt::Display;
+
+pub struct Day10;
+impl Solver for Day10 {
+  type Input = Vec<String>;
+  fn parse(&self, text: &str) -> Result<Vec<String>, ParseError> {
+    let rows: Vec<String> = text.lines().map(|row| row.trim().to_owned()).collect();
+    for (idx, row) in rows.iter().enumerate() {
+      if let Some(bad) = row.chars().find(|c| *c != '.' && *c != '#') {
+        return Err(ParseError::Line(
+          idx + 1,
+          format!("'{}' is not on the map", bad),
+        ));
+      }
+    }
+    Ok(rows)
+  }
+  fn part_a(&self, input: &Vec<String>) -> Result<impl Display, SolveError> {
+    a::solve(input).ok_or(SolveError::NoSolution)
+  }
+  fn part_b(&self, input: &Vec<String>) -> Result<impl Display, SolveError> {
+    b::solve(input).ok_or(SolveError::NoSolution)
+  }
 }
@@ -1,23 +1,32 @@
+use helpers::solver::{parse_program, ParseError, Part, SolveError, Solver};
 use helpers::vector::v2d::Point;
-use helpers::Solvers;
 use std::collections::HashMap;
-pub fn solve() -> Solvers {
-  Solvers::new(a::run, b::run)
+use std::fmt::Display;
+
+pub struct Day11;
+impl Solver for Day11 {
+  type Input = Vec<i64>;
+  fn parse(&self, text: &str) -> Result<Vec<i64>, ParseError> {
+    parse_program(text)
+  }
+  fn part_a(&self, input: &Vec<i64>) -> Result<impl Display, SolveError> {
+    Ok(a::solve(input))
+  }
+  fn part_b(&self, _input: &Vec<i64>) -> Result<impl Display, SolveError> {
+    Err::<String, _>(SolveError::Unimplemented)
+  }
+  fn parts(&self) -> Vec<Part> {
+    vec![Part::A]
+  }
 }
 mod a {
   use super::*;
-  pub fn run() -> String {
-    let program = helpers::loader::load_integer_row_list("./day/11/input.csv")[0].clone();
-    let mut johnny_5 = Robot::new(&program);
+  pub fn solve(program: &[i64]) -> usize {
+    let mut johnny_5 = Robot::new(program);
     let mut panel = Panel::new();
     johnny_5.run(&mut panel);
 
-    format!("{}", panel.map.len())
-  }
-}
-mod b {
-  pub fn run() -> String {
-    "bah".into()
+    panel.map.len()
   }
 }
 struct Panel {
@@ -113,8 +122,8 @@ impl Robot {
         State::Output(_id, value) => {
           if paint {
             match value {
-              0 => panel.paint_black(&self.position),
-              1 => panel.paint_white(&self.position),
+              0 => panel.paint_black(self.position),
+              1 => panel.paint_white(self.position),
               err => panic!("{} is invalid paint!", err),
             }
           } else {
@@ -144,7 +153,7 @@ mod tests {
   #[test]
   fn goodish() {
     // 2008 för lågt
-    let program = helpers::loader::load_integer_row_list("../day/11/input.csv")[0].clone();
+    let program = parse_program(&helpers::input::puzzle_input("day/11/input.csv")).unwrap();
     let mut johnny_5 = Robot::new(&program);
     let mut panel = Panel::new();
     johnny_5.run(&mut panel);
@@ -1,47 +1,46 @@
-use helpers::Solvers;
+use helpers::solver::{ParseError, SolveError, Solver};
+use std::fmt::Display;
 mod body;
 mod system;
 mod vector;
 use body::Body;
 use system::System;
-pub fn solve() -> Solvers {
-  Solvers::new(a::run, b::run)
-}
-mod a {
-  use super::{Body, System};
-  pub fn run() -> String {
-    /*
-    <x=4, y=1, z=1>
-    <x=11, y=-18, z=-1>
-    <x=-2, y=-10, z=-4>
-    <x=-7, y=-2, z=14>
-    */
-    let system = System::new(vec![
-      Body::new(4, 1, 1),
-      Body::new(11, -18, -1),
-      Body::new(-2, -10, -4),
-      Body::new(-7, -2, 14),
-    ]);
-    let result = system.run(1000);
-    format!("{}", result.total_energy())
+
+pub struct Day12;
+impl Solver for Day12 {
+  type Input = System;
+  fn parse(&self, text: &str) -> Result<System, ParseError> {
+    let mut bodies = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() {
+        continue;
+      }
+      let position = parse_position(line).ok_or_else(|| {
+        ParseError::Line(idx + 1, format!("'{}' is not <x=.., y=.., z=..>", line))
+      })?;
+      bodies.push(Body::new(position[0], position[1], position[2]));
+    }
+    Ok(System::new(bodies))
+  }
+  fn part_a(&self, input: &System) -> Result<impl Display, SolveError> {
+    Ok(input.run(1000).total_energy())
+  }
+  fn part_b(&self, input: &System) -> Result<impl Display, SolveError> {
+    Ok(input.run_until_repeat())
   }
 }
-mod b {
-  use super::{Body, System};
-  pub fn run() -> String {
-    /*
-    <x=4, y=1, z=1>
-    <x=11, y=-18, z=-1>
-    <x=-2, y=-10, z=-4>
-    <x=-7, y=-2, z=14>
-    */
-    let system = System::new(vec![
-      Body::new(4, 1, 1),
-      Body::new(11, -18, -1),
-      Body::new(-2, -10, -4),
-      Body::new(-7, -2, 14),
-    ]);
-    let result = system.run_until_repeat();
-    format!("{}", result)
+
+fn parse_position(line: &str) -> Option<Vec<i64>> {
+  let inner = line.strip_prefix('<')?.strip_suffix('>')?;
+  let values = inner
+    .split(',')
+    .zip(&["x=", "y=", "z="])
+    .map(|(value, axis)| value.trim().strip_prefix(axis)?.parse().ok())
+    .collect::<Option<Vec<i64>>>()?;
+  if values.len() == 3 && inner.split(',').count() == 3 {
+    Some(values)
+  } else {
+    None
   }
 }
@@ -1,14 +1,19 @@
-use helpers::Solvers;
-pub fn solve() -> Solvers {
-    Solvers::new(a::run, b::run)
-}
-mod a {
-    pub fn run() -> String {
-        "bah".into()
-    }
-}
-mod b {
-    pub fn run() -> String {
-        "bah".into()
-    }
+use helpers::solver::{ParseError, Part, SolveError, Solver};
+use std::fmt::Display;
+
+pub struct Day13;
+impl Solver for Day13 {
+  type Input = ();
+  fn parse(&self, _text: &str) -> Result<(), ParseError> {
+    Ok(())
+  }
+  fn part_a(&self, _input: &()) -> Result<impl Display, SolveError> {
+    Err::<String, _>(SolveError::Unimplemented)
+  }
+  fn part_b(&self, _input: &()) -> Result<impl Display, SolveError> {
+    Err::<String, _>(SolveError::Unimplemented)
+  }
+  fn parts(&self) -> Vec<Part> {
+    Vec::new()
+  }
 }
@@ -1,22 +1,44 @@
 mod parser;
-use helpers::Solvers;
+use helpers::solver::{ParseError, Part, SolveError, Solver};
+use std::fmt::Display;
 
-pub fn solve() -> Solvers {
-  Solvers::new(a::run, b::run)
+pub struct Day14;
+impl Solver for Day14 {
+  type Input = Vec<Reaction>;
+  fn parse(&self, text: &str) -> Result<Vec<Reaction>, ParseError> {
+    text
+      .lines()
+      .enumerate()
+      .filter(|(_, line)| !line.trim().is_empty())
+      .map(|(idx, line)| match parser::from_reaction(line.trim()) {
+        Ok(("", reaction)) => Ok(reaction),
+        Ok((rem, _)) => Err(ParseError::Line(
+          idx + 1,
+          format!("did not finish: {}", rem),
+        )),
+        Err(err) => Err(ParseError::Line(idx + 1, format!("{:?}", err))),
+      })
+      .collect()
+  }
+  fn part_a(&self, input: &Vec<Reaction>) -> Result<impl Display, SolveError> {
+    a::solve(input).ok_or(SolveError::NoSolution)
+  }
+  fn part_b(&self, _input: &Vec<Reaction>) -> Result<impl Display, SolveError> {
+    Err::<String, _>(SolveError::Unimplemented)
+  }
+  fn parts(&self) -> Vec<Part> {
+    vec![Part::A]
+  }
 }
 mod a {
   use super::*;
-  pub fn run() -> String {
-    let reactions: Vec<Reaction> = helpers::loader::read_as_iter("../day/14/input.cmds")
-      .map(parse_line)
-      .collect();
-    let (req, _prod) = NanoFactory::build(reactions).get_fuel();
-    format!("{}", req)
-  }
-}
-mod b {
-  pub fn run() -> String {
-    "bah".into()
+  pub fn solve(reactions: &[Reaction]) -> Option<i32> {
+    let factory = NanoFactory::build(reactions.to_vec());
+    if !factory.required_ore.contains_key(&CompoundType::Fuel) {
+      return None;
+    }
+    let (req, _prod) = factory.get_fuel();
+    Some(req)
   }
 }
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -67,6 +89,8 @@ impl Reaction {
 
 use std::collections::{HashMap, HashSet};
 
+type Recipes = HashMap<CompoundType, (i32, Vec<(CompoundType, i32)>)>;
+
 #[derive(Debug, PartialEq, Eq)]
 struct NanoFactory {
   required_ore: HashMap<CompoundType, RequiresToProduce>,
@@ -91,29 +115,54 @@ impl NanoFactory {
     }
     None
   }
+  fn ore_for(recipes: &Recipes, compound: CompoundType, ammount: i32) -> Option<i32> {
+    // Leftovers from earlier batches are kept as negative needs.
+    let mut needed = HashMap::new();
+    needed.insert(compound, ammount);
+    let mut ore = 0;
+    loop {
+      let next = needed
+        .iter()
+        .find(|(_, ammount)| **ammount > 0)
+        .map(|(compound, ammount)| (compound.clone(), *ammount));
+      let (compound, ammount) = match next {
+        Some(next) => next,
+        None => return Some(ore),
+      };
+      if compound == CompoundType::Ore {
+        ore += ammount;
+        needed.remove(&compound);
+        continue;
+      }
+      let (produces, inputs) = recipes.get(&compound)?;
+      let batches = (ammount + produces - 1) / produces;
+      needed.insert(compound, ammount - batches * produces);
+      for (input, requires) in inputs {
+        *needed.entry(input.clone()).or_insert(0) += batches * requires;
+      }
+    }
+  }
   pub fn build(reaction_list: Vec<Reaction>) -> NanoFactory {
-    let mut reactions: HashSet<Reaction> = HashSet::new();
+    let recipes: Recipes = reaction_list
+      .iter()
+      .map(|r| {
+        let (compound, produces) = r.output.to_tuple();
+        (compound, (produces, r.requires()))
+      })
+      .collect();
     let mut required_ore = HashMap::new();
-    reaction_list.iter().for_each(|r| {
-      // if r.is_primary() {
-      //   let (_compound_in, requires) = r.input[0].to_tuple();
-      //   if required_ore
-      //     .insert(compound_out, RequiresToProduce(requires, produces))
-      //     .is_some()
-      //   {
-      //     panic!("replaced something when creating the table");
-      //   }
-      // } else {
-      //   reactions.insert(r.clone());
-      // }
-    });
-
+    if let Some((produces, _)) = recipes.get(&CompoundType::Fuel) {
+      if let Some(ore) = NanoFactory::ore_for(&recipes, CompoundType::Fuel, *produces) {
+        required_ore.insert(CompoundType::Fuel, RequiresToProduce(ore, *produces));
+      }
+    }
     NanoFactory { required_ore }
   }
 }
 
 //helpers::loader::read_as_iter(path: &str)
 
+#[cfg(test)]
 fn parse_line(line: String) -> Reaction {
   match parser::from_reaction(&line) {
     Ok(("", reaction)) => reaction,
@@ -0,0 +1,139 @@
+use super::solver::{ParseError, Part};
+use std::collections::BTreeMap;
+
+// One answer per line as `year day part answer`, e.g. `2019 02 a 6627023`.
+// Answers spanning several lines are written with \n, a backslash as \\.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Answers {
+  entries: BTreeMap<(u32, u32, Part), String>,
+}
+
+impl Answers {
+  pub fn new() -> Answers {
+    Answers::default()
+  }
+  pub fn parse(text: &str) -> Result<Answers, ParseError> {
+    let mut answers = Answers::new();
+    for (idx, line) in text.lines().enumerate() {
+      let line = line.trim_start();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let error = |message: String| ParseError::Line(idx + 1, message);
+      let mut fields = line.splitn(4, ' ');
+      let mut number = |name: &str| {
+        let field = fields.next().unwrap_or("");
+        field
+          .parse::<u32>()
+          .map_err(|_| error(format!("'{}' is not a {}", field, name)))
+      };
+      let year = number("year")?;
+      let day = number("day")?;
+      let part = match fields.next() {
+        Some("a") => Part::A,
+        Some("b") => Part::B,
+        other => return Err(error(format!("'{}' is not a part", other.unwrap_or("")))),
+      };
+      let answer = match fields.next().map(unescape) {
+        Some(Ok(answer)) if !answer.is_empty() => answer,
+        Some(Err(message)) => return Err(error(message)),
+        _ => {
+          return Err(error(format!(
+            "{} day {} part {} has no answer",
+            year, day, part
+          )))
+        }
+      };
+      if answers.entries.insert((year, day, part), answer).is_some() {
+        return Err(error(format!(
+          "{} day {} part {} is listed twice",
+          year, day, part
+        )));
+      }
+    }
+    Ok(answers)
+  }
+  pub fn get(&self, year: u32, day: u32, part: Part) -> Option<&str> {
+    self.entries.get(&(year, day, part)).map(String::as_str)
+  }
+  pub fn insert(&mut self, year: u32, day: u32, part: Part, answer: &str) {
+    self.entries.insert((year, day, part), answer.to_owned());
+  }
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+}
+
+impl std::fmt::Display for Answers {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    for ((year, day, part), answer) in &self.entries {
+      writeln!(fmt, "{} {:02} {} {}", year, day, part, escape(answer))?;
+    }
+    Ok(())
+  }
+}
+
+pub fn escape(answer: &str) -> String {
+  answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+pub(crate) fn unescape(text: &str) -> Result<String, String> {
+  let mut answer = String::new();
+  let mut chars = text.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      answer.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('n') => answer.push('\n'),
+      Some('\\') => answer.push('\\'),
+      other => return Err(format!("'\\{}' is not an escape", other.unwrap_or(' '))),
+    }
+  }
+  Ok(answer)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn answers_1() {
+    let text = "# comment\n2019 02 a 6627023\n\n2019 8 b  #\\n# \\\\\n";
+    let answers = Answers::parse(text).unwrap();
+    assert_eq!(answers.len(), 2);
+    assert_eq!(answers.get(2019, 2, Part::A), Some("6627023"));
+    assert_eq!(answers.get(2019, 8, Part::B), Some(" #\n# \\"));
+    assert_eq!(answers.get(2019, 2, Part::B), None);
+    assert_eq!(Answers::parse(&answers.to_string()), Ok(answers));
+  }
+  #[test]
+  fn answers_errors() {
+    assert_eq!(
+      Answers::parse("2019 x a 1"),
+      Err(ParseError::Line(1, "'x' is not a day".into()))
+    );
+    assert_eq!(
+      Answers::parse("2019 1 c 1"),
+      Err(ParseError::Line(1, "'c' is not a part".into()))
+    );
+    assert_eq!(
+      Answers::parse("2019 1 a"),
+      Err(ParseError::Line(
+        1,
+        "2019 day 1 part a has no answer".into()
+      ))
+    );
+    assert_eq!(
+      Answers::parse("2019 1 a 1\n2019 01 a 2"),
+      Err(ParseError::Line(
+        2,
+        "2019 day 1 part a is listed twice".into()
+      ))
+    );
+  }
+}
@@ -0,0 +1,233 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const ROOT_VARIABLE: &str = "AOC_INPUT_ROOT";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Override {
+  Stdin,
+  Path(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+  NotFound(String, Vec<PathBuf>),
+  Io(PathBuf, String),
+  Stdin(String),
+}
+impl std::fmt::Display for Error {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Error::NotFound(file, searched) => {
+        write!(fmt, "Could not find {}, searched:", file)?;
+        for path in searched {
+          write!(fmt, "\n  {}", path.display())?;
+        }
+        Ok(())
+      }
+      Error::Io(path, message) => write!(fmt, "Could not read {}: {}.", path.display(), message),
+      Error::Stdin(message) => write!(fmt, "Could not read stdin: {}.", message),
+    }
+  }
+}
+
+// The directory holding day/, the parent of every crate in the workspace.
+pub fn workspace() -> PathBuf {
+  let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+  manifest.parent().unwrap_or(manifest).to_path_buf()
+}
+
+// Every year lives in a directory named after it, next to this workspace.
+pub fn year_dir(year: u32) -> PathBuf {
+  let workspace = workspace();
+  match workspace.parent() {
+    Some(repository) => repository.join(year.to_string()),
+    None => workspace,
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Resolver {
+  roots: Vec<PathBuf>,
+  input: Option<Override>,
+}
+
+impl Resolver {
+  pub fn new() -> Resolver {
+    Resolver::default()
+  }
+  // Tests only know the workspace they were built in, named after its year.
+  pub fn from_env() -> Resolver {
+    let workspace = workspace();
+    let year = workspace
+      .file_name()
+      .and_then(|name| name.to_str()?.parse().ok());
+    match year {
+      Some(year) => Resolver::for_year(year),
+      None => Resolver::new().with_root(workspace),
+    }
+  }
+  // Every root holds a directory per year, e.g. $AOC_INPUT_ROOT/2019/day/01/.
+  pub fn for_year(year: u32) -> Resolver {
+    let resolver = Resolver::new()
+      .with_root(year_dir(year))
+      .with_year_root(".", year);
+    match std::env::var_os(ROOT_VARIABLE) {
+      Some(root) => resolver.with_year_root(root, year),
+      None => resolver,
+    }
+  }
+  pub fn with_year_root<P: AsRef<Path>>(self, root: P, year: u32) -> Resolver {
+    self.with_root(root.as_ref().join(year.to_string()))
+  }
+  pub fn with_root<P: Into<PathBuf>>(mut self, root: P) -> Resolver {
+    self.roots.insert(0, root.into());
+    self
+  }
+  pub fn with_override(mut self, input: Override) -> Resolver {
+    self.input = Some(input);
+    self
+  }
+  pub fn roots(&self) -> &[PathBuf] {
+    &self.roots
+  }
+  pub fn candidates(&self, file: &str) -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    for path in self.roots.iter().map(|root| root.join(file)) {
+      if !candidates.contains(&path) {
+        candidates.push(path);
+      }
+    }
+    candidates
+  }
+  pub fn locate(&self, file: &str) -> Result<PathBuf, Error> {
+    let candidates = self.candidates(file);
+    match candidates.iter().find(|path| path.is_file()) {
+      Some(path) => Ok(path.clone()),
+      None => Err(Error::NotFound(file.into(), candidates)),
+    }
+  }
+  pub fn read(&self, file: Option<&str>) -> Result<String, Error> {
+    let path = match (&self.input, file) {
+      (Some(Override::Stdin), _) => {
+        let mut text = String::new();
+        return match std::io::stdin().read_to_string(&mut text) {
+          Ok(_) => Ok(text),
+          Err(err) => Err(Error::Stdin(err.to_string())),
+        };
+      }
+      (Some(Override::Path(path)), _) if path.is_file() => path.clone(),
+      (Some(Override::Path(path)), _) => {
+        return Err(Error::NotFound(
+          path.display().to_string(),
+          vec![path.clone()],
+        ))
+      }
+      (None, Some(file)) => self.locate(file)?,
+      (None, None) => return Ok(String::new()),
+    };
+    std::fs::read_to_string(&path).map_err(|err| Error::Io(path, err.to_string()))
+  }
+}
+
+// For tests, which should find their input wherever cargo runs them from.
+pub fn puzzle_input(file: &str) -> String {
+  match Resolver::from_env().read(Some(file)) {
+    Ok(text) => text,
+    Err(err) => panic!("{}", err),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn resolve_1() {
+    let resolver = Resolver::new().with_root("/nowhere").with_root(workspace());
+    assert_eq!(
+      resolver.locate("day/02/input.csv"),
+      Ok(workspace().join("day/02/input.csv"))
+    );
+    assert!(resolver
+      .read(Some("day/02/input.csv"))
+      .unwrap()
+      .starts_with("1,"));
+    assert_eq!(resolver.read(None), Ok(String::new()));
+    assert_eq!(year_dir(2019), workspace());
+    assert_eq!(Resolver::for_year(2019).roots().last(), Some(&workspace()));
+    assert_eq!(
+      Resolver::for_year(2020).roots().last(),
+      Some(&workspace().with_file_name("2020"))
+    );
+    assert!(Resolver::for_year(2020)
+      .roots()
+      .iter()
+      .all(|root| root.ends_with("2020")));
+  }
+  #[test]
+  fn years() {
+    let root = std::env::temp_dir().join(format!("aoc-years-{}", std::process::id()));
+    for year in &["2019", "2020"] {
+      std::fs::create_dir_all(root.join(year).join("day/01")).unwrap();
+      std::fs::write(root.join(year).join("day/01/input.txt"), year).unwrap();
+      std::fs::write(root.join(year).join("answers.txt"), year).unwrap();
+    }
+    std::fs::create_dir_all(root.join("2021")).unwrap();
+    let resolver = |year| Resolver::new().with_year_root(&root, year);
+    assert_eq!(
+      resolver(2019).read(Some("day/01/input.txt")),
+      Ok("2019".into())
+    );
+    assert_eq!(
+      resolver(2020).read(Some("day/01/input.txt")),
+      Ok("2020".into())
+    );
+    assert_eq!(resolver(2020).read(Some("answers.txt")), Ok("2020".into()));
+    assert_eq!(
+      resolver(2021).locate("answers.txt"),
+      Err(Error::NotFound(
+        "answers.txt".into(),
+        vec![root.join("2021/answers.txt")]
+      ))
+    );
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+  #[test]
+  fn not_found() {
+    let resolver = Resolver::new().with_root("/second").with_root("/first");
+    let err = resolver.read(Some("day/99/input.csv")).unwrap_err();
+    assert_eq!(
+      err,
+      Error::NotFound(
+        "day/99/input.csv".into(),
+        vec![
+          PathBuf::from("/first/day/99/input.csv"),
+          PathBuf::from("/second/day/99/input.csv")
+        ]
+      )
+    );
+    assert_eq!(
+      err.to_string(),
+      "Could not find day/99/input.csv, searched:\n  /first/day/99/input.csv\n  /second/day/99/input.csv"
+    );
+  }
+  #[test]
+  fn override_path() {
+    let resolver = Resolver::new()
+      .with_root("/nowhere")
+      .with_override(Override::Path(workspace().join("day/04/input.txt")));
+    assert_eq!(
+      resolver.read(Some("day/01/input.csv")),
+      Ok("125730-579381\n".into())
+    );
+    let missing = Resolver::new().with_override(Override::Path("/missing.txt".into()));
+    assert_eq!(
+      missing.read(None),
+      Err(Error::NotFound(
+        "/missing.txt".into(),
+        vec![PathBuf::from("/missing.txt")]
+      ))
+    );
+  }
+}
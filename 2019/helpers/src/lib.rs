@@ -1,19 +1,9 @@
+pub mod answers;
 pub mod coordinates;
+pub mod input;
 pub mod loader;
 pub mod mathy;
+pub mod rejected;
+pub mod solver;
 pub mod vector;
-pub struct Solvers(fn() -> String, fn() -> String);
-impl Solvers {
-  pub fn new(a: fn() -> String, b: fn() -> String) -> Solvers {
-    Solvers(a, b)
-  }
-}
-
-impl Solvers {
-  pub fn a(&self) -> String {
-    self.0()
-  }
-  pub fn b(&self) -> String {
-    self.1()
-  }
-}
+pub use solver::{ParseError, Part, Registry, SolveError, Solver};
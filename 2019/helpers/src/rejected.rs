@@ -0,0 +1,209 @@
+use super::answers::{escape, unescape};
+use super::solver::{ParseError, Part};
+
+// Answers the puzzle site turned down, one per line as `year day part verdict answer`
+// where the verdict is high, low or wrong, e.g. `2019 11 a low 2008`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+  TooHigh,
+  TooLow,
+  Wrong,
+}
+impl std::fmt::Display for Verdict {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Verdict::TooHigh => write!(fmt, "high"),
+      Verdict::TooLow => write!(fmt, "low"),
+      Verdict::Wrong => write!(fmt, "wrong"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conflict {
+  Rejected(Verdict),
+  TooHigh(String),
+  TooLow(String),
+}
+impl std::fmt::Display for Conflict {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Conflict::Rejected(Verdict::Wrong) => write!(fmt, "this answer was already rejected"),
+      Conflict::Rejected(verdict) => {
+        write!(fmt, "this answer was already rejected as too {}", verdict)
+      }
+      Conflict::TooHigh(bound) => write!(fmt, "{} was too high, so this is too", bound),
+      Conflict::TooLow(bound) => write!(fmt, "{} was too low, so this is too", bound),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rejection {
+  year: u32,
+  day: u32,
+  part: Part,
+  verdict: Verdict,
+  answer: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rejected {
+  entries: Vec<Rejection>,
+}
+
+impl Rejected {
+  pub fn new() -> Rejected {
+    Rejected::default()
+  }
+  pub fn parse(text: &str) -> Result<Rejected, ParseError> {
+    let mut rejected = Rejected::new();
+    for (idx, line) in text.lines().enumerate() {
+      let line = line.trim_start();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let error = |message: String| ParseError::Line(idx + 1, message);
+      let fields: Vec<&str> = line.splitn(5, ' ').collect();
+      if fields.len() != 5 || fields[4].is_empty() {
+        return Err(error(format!(
+          "'{}' is not `year day part verdict answer`",
+          line
+        )));
+      }
+      let number = |field: &str, name: &str| {
+        field
+          .parse::<u32>()
+          .map_err(|_| error(format!("'{}' is not a {}", field, name)))
+      };
+      let part = match fields[2] {
+        "a" => Part::A,
+        "b" => Part::B,
+        other => return Err(error(format!("'{}' is not a part", other))),
+      };
+      let verdict = match fields[3] {
+        "high" => Verdict::TooHigh,
+        "low" => Verdict::TooLow,
+        "wrong" => Verdict::Wrong,
+        other => return Err(error(format!("'{}' is not high, low or wrong", other))),
+      };
+      rejected.record(
+        number(fields[0], "year")?,
+        number(fields[1], "day")?,
+        part,
+        verdict,
+        &unescape(fields[4]).map_err(error)?,
+      );
+    }
+    Ok(rejected)
+  }
+  pub fn record(&mut self, year: u32, day: u32, part: Part, verdict: Verdict, answer: &str) {
+    let rejection = Rejection {
+      year,
+      day,
+      part,
+      verdict,
+      answer: answer.to_owned(),
+    };
+    if !self.entries.contains(&rejection) {
+      self.entries.push(rejection);
+    }
+  }
+  pub fn check(&self, year: u32, day: u32, part: Part, answer: &str) -> Vec<Conflict> {
+    let value = answer.trim().parse::<i128>().ok();
+    self
+      .entries
+      .iter()
+      .filter(|r| r.year == year && r.day == day && r.part == part)
+      .filter_map(|r| {
+        if r.answer == answer {
+          return Some(Conflict::Rejected(r.verdict));
+        }
+        match (r.verdict, value, r.answer.trim().parse::<i128>().ok()) {
+          (Verdict::TooHigh, Some(value), Some(bound)) if value >= bound => {
+            Some(Conflict::TooHigh(r.answer.clone()))
+          }
+          (Verdict::TooLow, Some(value), Some(bound)) if value <= bound => {
+            Some(Conflict::TooLow(r.answer.clone()))
+          }
+          _ => None,
+        }
+      })
+      .collect()
+  }
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+}
+
+impl std::fmt::Display for Rejected {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    for r in &self.entries {
+      writeln!(
+        fmt,
+        "{} {:02} {} {} {}",
+        r.year,
+        r.day,
+        r.part,
+        r.verdict,
+        escape(&r.answer)
+      )?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rejected_1() {
+    let text = "# notes\n2019 11 a low 2008\n2019 10 b wrong 3209\n2019 10 b high 2000\n";
+    let rejected = Rejected::parse(text).unwrap();
+    assert_eq!(rejected.len(), 3);
+    assert_eq!(
+      rejected.check(2019, 11, Part::A, "2008"),
+      vec![Conflict::Rejected(Verdict::TooLow)]
+    );
+    assert_eq!(
+      rejected.check(2019, 11, Part::A, "1999"),
+      vec![Conflict::TooLow("2008".into())]
+    );
+    assert_eq!(rejected.check(2019, 11, Part::A, "2009"), vec![]);
+    assert_eq!(rejected.check(2019, 11, Part::B, "2008"), vec![]);
+    assert_eq!(
+      rejected.check(2019, 10, Part::B, "3209"),
+      vec![
+        Conflict::Rejected(Verdict::Wrong),
+        Conflict::TooHigh("2000".into())
+      ]
+    );
+    assert_eq!(rejected.check(2019, 10, Part::B, "not a number"), vec![]);
+    assert_eq!(Rejected::parse(&rejected.to_string()), Ok(rejected));
+    assert_eq!(
+      Conflict::TooLow("2008".into()).to_string(),
+      "2008 was too low, so this is too"
+    );
+  }
+  #[test]
+  fn rejected_errors() {
+    assert_eq!(
+      Rejected::parse("2019 11 a 2008"),
+      Err(ParseError::Line(
+        1,
+        "'2019 11 a 2008' is not `year day part verdict answer`".into()
+      ))
+    );
+    assert_eq!(
+      Rejected::parse("\n2019 11 a lower 2008"),
+      Err(ParseError::Line(
+        2,
+        "'lower' is not high, low or wrong".into()
+      ))
+    );
+  }
+}
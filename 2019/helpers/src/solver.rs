@@ -0,0 +1,273 @@
+use std::any::Any;
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+  A,
+  B,
+}
+impl Display for Part {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Part::A => write!(fmt, "a"),
+      Part::B => write!(fmt, "b"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+  Line(usize, String),
+  Format(String),
+}
+impl Display for ParseError {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      ParseError::Line(line, message) => write!(fmt, "Parse error on line {}: {}.", line, message),
+      ParseError::Format(message) => write!(fmt, "Parse error: {}.", message),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+  NoSolution,
+  Unimplemented,
+  Failed(String),
+}
+impl Display for SolveError {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      SolveError::NoSolution => write!(fmt, "No solution found."),
+      SolveError::Unimplemented => write!(fmt, "Not implemented yet."),
+      SolveError::Failed(message) => write!(fmt, "Failed: {}", message),
+    }
+  }
+}
+
+pub trait Solver {
+  type Input;
+  fn parse(&self, text: &str) -> Result<Self::Input, ParseError>;
+  fn part_a(&self, input: &Self::Input) -> Result<impl Display, SolveError>;
+  fn part_b(&self, input: &Self::Input) -> Result<impl Display, SolveError>;
+  // Parts left out are stubs, they answer SolveError::Unimplemented.
+  fn parts(&self) -> Vec<Part> {
+    vec![Part::A, Part::B]
+  }
+}
+
+// Solvers have different input types, the registry holds them through this.
+pub trait Puzzle: Send + Sync {
+  fn load(&self, text: &str) -> Result<Box<dyn Any + Send + Sync>, ParseError>;
+  fn answer(&self, part: Part, input: &dyn Any) -> Result<String, SolveError>;
+  fn parts(&self) -> Vec<Part>;
+}
+impl<S> Puzzle for S
+where
+  S: Solver + Send + Sync,
+  S::Input: Send + Sync + 'static,
+{
+  fn load(&self, text: &str) -> Result<Box<dyn Any + Send + Sync>, ParseError> {
+    self
+      .parse(text)
+      .map(|input| Box::new(input) as Box<dyn Any + Send + Sync>)
+  }
+  fn answer(&self, part: Part, input: &dyn Any) -> Result<String, SolveError> {
+    let input = input
+      .downcast_ref::<S::Input>()
+      .ok_or_else(|| SolveError::Failed("input was parsed by another solver".into()))?;
+    if !Solver::parts(self).contains(&part) {
+      return Err(SolveError::Unimplemented);
+    }
+    match part {
+      Part::A => self.part_a(input).map(|answer| answer.to_string()),
+      Part::B => self.part_b(input).map(|answer| answer.to_string()),
+    }
+  }
+  fn parts(&self) -> Vec<Part> {
+    Solver::parts(self)
+  }
+}
+
+pub struct Entry {
+  pub year: u32,
+  pub day: u32,
+  pub input: Option<&'static str>,
+  pub puzzle: Box<dyn Puzzle>,
+}
+
+#[derive(Default)]
+pub struct Registry {
+  entries: Vec<Entry>,
+}
+
+impl Registry {
+  pub fn new() -> Registry {
+    Registry::default()
+  }
+  // Inputs are relative to the directory of their year, e.g. 2019/.
+  pub fn register<S>(&mut self, year: u32, day: u32, input: Option<&'static str>, solver: S)
+  where
+    S: Solver + Send + Sync + 'static,
+    S::Input: Send + Sync + 'static,
+  {
+    self
+      .entries
+      .retain(|entry| (entry.year, entry.day) != (year, day));
+    self.entries.push(Entry {
+      year,
+      day,
+      input,
+      puzzle: Box::new(solver),
+    });
+    self.entries.sort_by_key(|entry| (entry.year, entry.day));
+  }
+  pub fn get(&self, year: u32, day: u32) -> Option<&Entry> {
+    self
+      .entries
+      .iter()
+      .find(|entry| (entry.year, entry.day) == (year, day))
+  }
+  pub fn iter(&self) -> impl Iterator<Item = &Entry> {
+    self.entries.iter()
+  }
+  pub fn years(&self) -> Vec<u32> {
+    let mut years: Vec<u32> = self.entries.iter().map(|entry| entry.year).collect();
+    years.dedup();
+    years
+  }
+  pub fn days(&self, year: u32) -> impl Iterator<Item = u32> + '_ {
+    self
+      .entries
+      .iter()
+      .filter(move |entry| entry.year == year)
+      .map(|entry| entry.day)
+  }
+}
+
+pub fn parse_lines<T>(text: &str) -> Result<Vec<T>, ParseError>
+where
+  T: FromStr,
+  T::Err: Display,
+{
+  text
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty())
+    .map(|(idx, line)| {
+      line
+        .trim()
+        .parse()
+        .map_err(|err| ParseError::Line(idx + 1, format!("{}", err)))
+    })
+    .collect()
+}
+
+pub fn parse_program(text: &str) -> Result<Vec<i64>, ParseError> {
+  let (idx, line) = text
+    .lines()
+    .enumerate()
+    .find(|(_, line)| !line.trim().is_empty())
+    .ok_or_else(|| ParseError::Format("no program found".into()))?;
+  line
+    .trim()
+    .split(',')
+    .map(|value| {
+      value
+        .trim()
+        .parse()
+        .map_err(|_| ParseError::Line(idx + 1, format!("'{}' is not a number", value)))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct Sum;
+  impl Solver for Sum {
+    type Input = Vec<i64>;
+    fn parse(&self, text: &str) -> Result<Vec<i64>, ParseError> {
+      parse_lines(text)
+    }
+    fn part_a(&self, input: &Vec<i64>) -> Result<impl Display, SolveError> {
+      Ok(input.iter().sum::<i64>())
+    }
+    fn part_b(&self, input: &Vec<i64>) -> Result<impl Display, SolveError> {
+      input
+        .iter()
+        .max()
+        .map(|max| format!("max {}", max))
+        .ok_or(SolveError::NoSolution)
+    }
+  }
+
+  struct Stub;
+  impl Solver for Stub {
+    type Input = ();
+    fn parse(&self, _text: &str) -> Result<(), ParseError> {
+      Ok(())
+    }
+    fn part_a(&self, _input: &()) -> Result<impl Display, SolveError> {
+      Ok(1)
+    }
+    fn part_b(&self, _input: &()) -> Result<impl Display, SolveError> {
+      Ok("unfinished")
+    }
+    fn parts(&self) -> Vec<Part> {
+      vec![Part::A]
+    }
+  }
+
+  #[test]
+  fn registry_1() {
+    let mut registry = Registry::new();
+    registry.register(2019, 3, None, Sum);
+    registry.register(2019, 1, Some("day/01/input.csv"), Sum);
+    registry.register(2015, 2, None, Sum);
+    assert_eq!(registry.years(), vec![2015, 2019]);
+    assert_eq!(registry.days(2019).collect::<Vec<u32>>(), vec![1, 3]);
+    let entry = registry.get(2019, 1).unwrap();
+    let input = entry.puzzle.load("1\n\n2\n3\n").unwrap();
+    assert_eq!(entry.puzzle.answer(Part::A, input.as_ref()), Ok("6".into()));
+    assert_eq!(
+      entry.puzzle.answer(Part::B, input.as_ref()),
+      Ok("max 3".into())
+    );
+    let empty = entry.puzzle.load("").unwrap();
+    assert_eq!(
+      entry.puzzle.answer(Part::B, empty.as_ref()),
+      Err(SolveError::NoSolution)
+    );
+    assert!(registry.get(2019, 2).is_none());
+    assert!(registry.get(2015, 2).is_some());
+    assert_eq!(entry.puzzle.parts(), vec![Part::A, Part::B]);
+    registry.register(2019, 4, None, Stub);
+    let stub = registry.get(2019, 4).unwrap();
+    assert_eq!(stub.puzzle.parts(), vec![Part::A]);
+    let input = stub.puzzle.load("").unwrap();
+    assert_eq!(stub.puzzle.answer(Part::A, input.as_ref()), Ok("1".into()));
+    assert_eq!(
+      stub.puzzle.answer(Part::B, input.as_ref()),
+      Err(SolveError::Unimplemented)
+    );
+  }
+  #[test]
+  fn parse_errors() {
+    assert_eq!(
+      parse_lines::<i64>("1\nx\n"),
+      Err(ParseError::Line(2, "invalid digit found in string".into()))
+    );
+    assert_eq!(parse_program("\n1,-2, 3\n"), Ok(vec![1, -2, 3]));
+    assert_eq!(
+      parse_program("1,,3"),
+      Err(ParseError::Line(1, "'' is not a number".into()))
+    );
+    assert_eq!(
+      parse_program(""),
+      Err(ParseError::Format("no program found".into()))
+    );
+  }
+}
@@ -1,49 +1,147 @@
-use super::state::State;
+use super::event::Event;
 use super::Computer;
+use rayon::prelude::*;
+use tracing::{debug, trace};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+  Chain,
+  Feedback,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Message {
+  pub from: Option<usize>,
+  pub to: usize,
+  pub value: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting {
+  pub phases: Vec<i64>,
+  pub signal: i64,
+}
+
+pub struct Permutations {
+  alphabet: Vec<i64>,
+  order: Option<Vec<usize>>,
+}
+
+impl Permutations {
+  pub fn new(alphabet: &[i64]) -> Permutations {
+    Permutations {
+      alphabet: alphabet.to_vec(),
+      order: Some((0..alphabet.len()).collect()),
+    }
+  }
+}
+
+impl Iterator for Permutations {
+  type Item = Vec<i64>;
+  fn next(&mut self) -> Option<Vec<i64>> {
+    let alphabet = &self.alphabet;
+    let order = self.order.as_mut()?;
+    let current = order.iter().map(|idx| alphabet[*idx]).collect();
+    match (1..order.len())
+      .rev()
+      .find(|idx| order[idx - 1] < order[*idx])
+    {
+      Some(pivot) => {
+        let swap = (pivot..order.len())
+          .rev()
+          .find(|idx| order[*idx] > order[pivot - 1])
+          .unwrap();
+        order.swap(pivot - 1, swap);
+        order[pivot..].reverse();
+      }
+      None => self.order = None,
+    }
+    Some(current)
+  }
+}
+
+pub fn settings<'a>(
+  program: &'a [i64],
+  alphabet: &[i64],
+  mode: Mode,
+) -> impl ParallelIterator<Item = Setting> + 'a {
+  Permutations::new(alphabet).par_bridge().map(move |phases| {
+    let signal = Amplifiers::with_mode(program, &phases, mode).run_to_end(Some(0));
+    Setting { phases, signal }
+  })
+}
+
+pub fn optimize<F>(program: &[i64], alphabet: &[i64], mode: Mode, report: F) -> Option<Setting>
+where
+  F: Fn(&Setting) + Sync + Send,
+{
+  settings(program, alphabet, mode)
+    .inspect(report)
+    .max_by(|a, b| {
+      a.signal
+        .cmp(&b.signal)
+        .then_with(|| b.phases.cmp(&a.phases))
+    })
+}
+
 pub struct Amplifiers {
   amplifiers: Vec<Computer>,
   buffer: Vec<(usize, i64)>,
   send_to: Vec<usize>,
+  messages: Vec<Message>,
 }
 impl Amplifiers {
   pub fn new(program: &[i64], pss: &[i64]) -> Amplifiers {
+    Amplifiers::with_mode(program, pss, Mode::Feedback)
+  }
+  pub fn with_mode(program: &[i64], pss: &[i64], mode: Mode) -> Amplifiers {
     let count = pss.len();
+    let wrap = match mode {
+      Mode::Chain => count + 1,
+      Mode::Feedback => count,
+    };
     Amplifiers {
       amplifiers: (0..count).map(|id| Computer::load(id, program)).collect(),
       buffer: pss.iter().cloned().enumerate().collect(),
-      send_to: (0..count).map(|dest| (dest + 1) % count).collect(),
+      send_to: (0..count).map(|dest| (dest + 1) % wrap).collect(),
+      messages: pss
+        .iter()
+        .enumerate()
+        .map(|(to, value)| Message {
+          from: None,
+          to,
+          value: *value,
+        })
+        .collect(),
     }
   }
+  pub fn messages(&self) -> &[Message] {
+    &self.messages
+  }
   fn post_messsage(&mut self, from: usize, message: i64) {
     assert!(self.send_to.len() > from);
-    self.buffer.push((self.send_to[from], message))
-  }
-
-  fn print_buffer(&self) {
-    println!("buffer depth: {}", self.buffer.len());
-    self
-      .buffer
-      .iter()
-      .cloned()
-      .for_each(|(id, msg)| println!("({}, {})", id, msg));
+    let to = self.send_to[from];
+    debug!(from, to, value = message, "amplifier message");
+    self.messages.push(Message {
+      from: Some(from),
+      to,
+      value: message,
+    });
+    self.buffer.push((to, message))
   }
 
   fn run(&mut self) -> Option<i64> {
     let mut halted: std::collections::HashSet<usize> = (0..self.amplifiers.len()).collect();
     let mut idx = 0;
     loop {
-      match self.amplifiers[idx].run() {
-        State::Crashed => panic!("amplifier {} crashed", idx),
-        State::Halted => {
+      match self.amplifiers[idx].resume() {
+        Event::Faulted { error, .. } => panic!("amplifier {} crashed: {}", idx, error),
+        Event::Halted { .. } => {
           halted.remove(&idx);
         }
-        State::Ready => (),
-        State::Running => (),
-        State::Output(id, msg) => {
-          println!("{} sending {}", id, msg);
-          self.post_messsage(id, msg)
-        }
-        State::Input => {
+        Event::Breakpoint { .. } | Event::BudgetExhausted { .. } => (),
+        Event::Output { value, .. } => self.post_messsage(idx, value),
+        Event::Input { .. } => {
           if let Some(pos) = self.buffer.iter().position(|(addr, _value)| *addr == idx) {
             let (_addr, value) = self.buffer.remove(pos);
             if !self.amplifiers[idx].input_value(value) {
@@ -62,8 +160,13 @@ impl Amplifiers {
   pub fn run_to_end(&mut self, init: Option<i64>) -> i64 {
     if let Some(initing_value) = init {
       self.buffer.push((0, initing_value));
+      self.messages.push(Message {
+        from: None,
+        to: 0,
+        value: initing_value,
+      });
     }
-    self.print_buffer();
+    trace!(depth = self.buffer.len(), buffer = ?self.buffer, "amplifiers starting");
     self.run().unwrap()
   }
 }
@@ -71,6 +174,54 @@ impl Amplifiers {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use std::sync::Mutex;
+
+  #[test]
+  fn permutations_1() {
+    let all: Vec<Vec<i64>> = Permutations::new(&[1, 2, 3]).collect();
+    assert_eq!(
+      all,
+      vec![
+        vec![1, 2, 3],
+        vec![1, 3, 2],
+        vec![2, 1, 3],
+        vec![2, 3, 1],
+        vec![3, 1, 2],
+        vec![3, 2, 1]
+      ]
+    );
+    assert_eq!(Permutations::new(&[0, 1, 2, 3, 4]).count(), 120);
+    assert_eq!(Permutations::new(&[]).count(), 1);
+  }
+  #[test]
+  fn optimize_chain() {
+    let program = vec![
+      3, 23, 3, 24, 1002, 24, 10, 24, 1002, 23, -1, 23, 101, 5, 23, 23, 1, 24, 23, 23, 4, 23, 99,
+      0, 0,
+    ];
+    let seen = Mutex::new(Vec::new());
+    let best = optimize(&program, &[0, 1, 2, 3, 4], Mode::Chain, |setting| {
+      seen.lock().unwrap().push(setting.clone())
+    });
+    assert_eq!(
+      best,
+      Some(Setting {
+        phases: vec![0, 1, 2, 3, 4],
+        signal: 54321
+      })
+    );
+    assert_eq!(seen.lock().unwrap().len(), 120);
+  }
+  #[test]
+  fn optimize_feedback() {
+    let program = vec![
+      3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1, 28,
+      1005, 28, 6, 99, 0, 0, 5,
+    ];
+    let best = optimize(&program, &[5, 6, 7, 8, 9], Mode::Feedback, |_| ()).unwrap();
+    assert_eq!(best.phases, vec![9, 8, 7, 6, 5]);
+    assert_eq!(best.signal, 139_629_729);
+  }
   #[test]
   fn test_1() {
     let program = vec![
@@ -80,6 +231,75 @@ mod tests {
     let mut amps = Amplifiers::new(&program, &[9, 8, 7, 6, 5]);
     let actual = amps.run_to_end(Some(0));
     assert_eq!(actual, 139_629_729);
+    let messages = amps.messages();
+    assert_eq!(
+      messages[..6],
+      [
+        Message {
+          from: None,
+          to: 0,
+          value: 9
+        },
+        Message {
+          from: None,
+          to: 1,
+          value: 8
+        },
+        Message {
+          from: None,
+          to: 2,
+          value: 7
+        },
+        Message {
+          from: None,
+          to: 3,
+          value: 6
+        },
+        Message {
+          from: None,
+          to: 4,
+          value: 5
+        },
+        Message {
+          from: None,
+          to: 0,
+          value: 0
+        },
+      ]
+    );
+    assert_eq!(
+      messages.last(),
+      Some(&Message {
+        from: Some(4),
+        to: 0,
+        value: 139_629_729
+      })
+    );
+    assert_eq!(messages.iter().filter(|m| m.from == Some(2)).count(), 5);
+  }
+  #[test]
+  fn messages_chain() {
+    let program = vec![
+      3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+    ];
+    let mut amps = Amplifiers::with_mode(&program, &[4, 3, 2, 1, 0], Mode::Chain);
+    assert_eq!(amps.run_to_end(Some(0)), 43210);
+    let sent: Vec<(Option<usize>, usize, i64)> = amps
+      .messages()
+      .iter()
+      .skip(6)
+      .map(|m| (m.from, m.to, m.value))
+      .collect();
+    assert_eq!(
+      sent,
+      vec![
+        (Some(0), 1, 4),
+        (Some(1), 2, 43),
+        (Some(2), 3, 432),
+        (Some(3), 4, 4321),
+        (Some(4), 5, 43210)
+      ]
+    );
   }
   #[test]
   fn test_2() {
@@ -0,0 +1,258 @@
+use super::Error;
+use std::collections::BTreeMap;
+
+// Mnemonic, opcode, operand count and the position of the destination
+// operand if the instruction writes memory.
+const MNEMONICS: &[(&str, i64, usize, Option<usize>)] = &[
+  ("add", 1, 3, Some(2)),
+  ("mul", 2, 3, Some(2)),
+  ("in", 3, 1, Some(0)),
+  ("out", 4, 1, None),
+  ("jt", 5, 2, None),
+  ("jf", 6, 2, None),
+  ("lt", 7, 3, Some(2)),
+  ("eq", 8, 3, Some(2)),
+  ("arb", 9, 1, None),
+  ("hlt", 99, 0, None),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Word {
+  Number(i64),
+  Label(String, i64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+  Imm(Word),
+  Pos(Word),
+  Rel(i64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object {
+  name: String,
+  pub(super) code: Vec<i64>,
+  pub(super) relocations: Vec<usize>,
+  pub(super) references: Vec<(usize, String)>,
+  pub(super) exports: BTreeMap<String, i64>,
+}
+
+impl Object {
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+  pub fn len(&self) -> usize {
+    self.code.len()
+  }
+  pub fn is_empty(&self) -> bool {
+    self.code.is_empty()
+  }
+  pub fn exports(&self) -> impl Iterator<Item = &str> {
+    self.exports.keys().map(String::as_str)
+  }
+  pub fn imports(&self) -> impl Iterator<Item = &str> {
+    self.references.iter().map(|(_, name)| name.as_str())
+  }
+}
+
+pub fn assemble(name: &str, source: &str) -> Result<Object, Error> {
+  let mut lines = Vec::new();
+  let mut labels = BTreeMap::new();
+  let mut globals = Vec::new();
+  let mut offset = 0;
+  for (idx, line) in source.lines().enumerate() {
+    let line_no = idx + 1;
+    let mut line = match line.find(';') {
+      Some(pos) => &line[..pos],
+      None => line,
+    }
+    .trim();
+    while let Some(colon) = line.find(':') {
+      let label = line[..colon].trim();
+      if !is_name(label) {
+        return Err(Error::Syntax(line_no, format!("bad label '{}'", label)));
+      }
+      if labels.insert(label.to_owned(), offset).is_some() {
+        return Err(Error::Name(
+          line_no,
+          format!("label {} is defined twice", label),
+        ));
+      }
+      line = line[colon + 1..].trim();
+    }
+    if line.is_empty() {
+      continue;
+    }
+    let (mnemonic, rest) = match line.find(char::is_whitespace) {
+      Some(pos) => (&line[..pos], line[pos..].trim()),
+      None => (line, ""),
+    };
+    let args: Vec<&str> = if rest.is_empty() {
+      Vec::new()
+    } else {
+      rest.split(',').map(str::trim).collect()
+    };
+    if mnemonic == ".global" {
+      for arg in args {
+        if !is_name(arg) {
+          return Err(Error::Syntax(line_no, format!("bad symbol '{}'", arg)));
+        }
+        globals.push((line_no, arg.to_owned()));
+      }
+      continue;
+    }
+    let words = if mnemonic == "data" {
+      args
+        .iter()
+        .map(|arg| word(line_no, arg).map(Operand::Imm))
+        .collect::<Result<Vec<_>, _>>()?
+    } else {
+      instruction(line_no, mnemonic, &args)?
+    };
+    offset += words.len() as i64;
+    lines.push(words);
+  }
+
+  let mut object = Object {
+    name: name.to_owned(),
+    code: Vec::new(),
+    relocations: Vec::new(),
+    references: Vec::new(),
+    exports: BTreeMap::new(),
+  };
+  for (line_no, global) in globals {
+    match labels.get(&global) {
+      Some(offset) => {
+        object.exports.insert(global, *offset);
+      }
+      None => {
+        return Err(Error::Name(
+          line_no,
+          format!("{} is exported but not defined", global),
+        ))
+      }
+    }
+  }
+  for words in lines {
+    for operand in words {
+      let at = object.code.len();
+      match operand {
+        Operand::Rel(offset) => object.code.push(offset),
+        Operand::Imm(Word::Number(value)) => object.code.push(value),
+        // Position mode addresses are local to the module, same as labels.
+        Operand::Pos(Word::Number(addr)) => {
+          object.code.push(addr);
+          object.relocations.push(at);
+        }
+        Operand::Imm(Word::Label(label, addend)) | Operand::Pos(Word::Label(label, addend)) => {
+          match labels.get(&label) {
+            Some(offset) => {
+              object.code.push(offset + addend);
+              object.relocations.push(at);
+            }
+            None => {
+              object.code.push(addend);
+              object.references.push((at, label));
+            }
+          }
+        }
+      }
+    }
+  }
+  Ok(object)
+}
+
+fn instruction(line_no: usize, mnemonic: &str, args: &[&str]) -> Result<Vec<Operand>, Error> {
+  let (opcode, count, dest) = match MNEMONICS.iter().find(|(name, ..)| *name == mnemonic) {
+    Some((_, opcode, count, dest)) => (*opcode, *count, *dest),
+    None => {
+      return Err(Error::Syntax(
+        line_no,
+        format!("unknown instruction '{}'", mnemonic),
+      ))
+    }
+  };
+  if args.len() != count {
+    return Err(Error::Syntax(
+      line_no,
+      format!("{} takes {} operands, not {}", mnemonic, count, args.len()),
+    ));
+  }
+  let operands = args
+    .iter()
+    .map(|arg| operand(line_no, arg))
+    .collect::<Result<Vec<_>, _>>()?;
+  let mut code = opcode;
+  let mut factor = 100;
+  for (idx, (arg, operand)) in args.iter().zip(&operands).enumerate() {
+    code += factor
+      * match operand {
+        Operand::Pos(_) => 0,
+        Operand::Imm(_) if dest == Some(idx) => {
+          return Err(Error::Syntax(
+            line_no,
+            format!("'{}' can not be a destination", arg),
+          ))
+        }
+        Operand::Imm(_) => 1,
+        Operand::Rel(_) => 2,
+      };
+    factor *= 10;
+  }
+  let mut words = vec![Operand::Imm(Word::Number(code))];
+  words.extend(operands);
+  Ok(words)
+}
+
+fn operand(line_no: usize, arg: &str) -> Result<Operand, Error> {
+  if let Some(rest) = arg.strip_prefix('#') {
+    return Ok(Operand::Imm(word(line_no, rest.trim())?));
+  }
+  if let Some(rest) = arg.strip_prefix("rb") {
+    let rest: String = rest.chars().filter(|c| !c.is_whitespace()).collect();
+    if rest.is_empty() {
+      return Ok(Operand::Rel(0));
+    }
+    if rest.starts_with(['+', '-']) {
+      return number(line_no, rest.trim_start_matches('+')).map(Operand::Rel);
+    }
+  }
+  word(line_no, arg).map(Operand::Pos)
+}
+
+fn word(line_no: usize, arg: &str) -> Result<Word, Error> {
+  if arg.starts_with(|c: char| c == '-' || c.is_ascii_digit()) {
+    return number(line_no, arg).map(Word::Number);
+  }
+  let split = arg.find(['+', '-']).unwrap_or(arg.len());
+  let label = arg[..split].trim();
+  if !is_name(label) {
+    return Err(Error::Syntax(line_no, format!("bad operand '{}'", arg)));
+  }
+  let addend: String = arg[split..]
+    .chars()
+    .filter(|c| !c.is_whitespace())
+    .collect();
+  let addend = match addend.as_str() {
+    "" => 0,
+    addend => number(line_no, addend.trim_start_matches('+'))?,
+  };
+  Ok(Word::Label(label.to_owned(), addend))
+}
+
+fn number(line_no: usize, text: &str) -> Result<i64, Error> {
+  text
+    .parse()
+    .map_err(|_| Error::Syntax(line_no, format!("'{}' is not a number", text)))
+}
+
+fn is_name(text: &str) -> bool {
+  let mut chars = text.chars();
+  match chars.next() {
+    Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+      chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+    _ => false,
+  }
+}
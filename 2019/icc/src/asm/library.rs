@@ -0,0 +1,114 @@
+use super::assembler::{assemble, Object};
+
+// Routines take their return address at rb+0 and arguments from rb+1 on,
+// returning their result in rb+1. Scratch space above the arguments
+// belongs to the callee, so callers leave their own frame below rb.
+
+const PRINT_NUMBER: &str = "
+.global print_number
+print_number:           ; rb+1 value, writes it as ascii
+  lt rb+1, #0, rb+2
+  jf rb+2, #positive
+  out #45
+  mul rb+1, #-1, rb+1
+positive:
+  add #back, #0, rb+3
+  add rb+1, #0, rb+4
+  add #1, #0, rb+5
+  arb #3
+  jt #1, #digits
+back:
+  arb #-3
+  jt #1, rb
+
+digits:                 ; rb+1 value, rb+2 power of ten
+  lt rb+2, #1000000000000000000, rb+3
+  jf rb+3, #emit
+  mul rb+2, #10, rb+3
+  lt rb+1, rb+3, rb+4
+  jt rb+4, #emit
+  add #higher, #0, rb+5
+  add rb+1, #0, rb+6
+  add rb+3, #0, rb+7
+  arb #5
+  jt #1, #digits
+higher:
+  arb #-5
+  add rb+6, #0, rb+1
+emit:
+  add #48, #0, rb+4
+count:
+  lt rb+1, rb+2, rb+3
+  jt rb+3, #done
+  mul rb+2, #-1, rb+3
+  add rb+1, rb+3, rb+1
+  add rb+4, #1, rb+4
+  jt #1, #count
+done:
+  out rb+4
+  jt #1, rb
+";
+
+const READ_LINE: &str = "
+.global read_line
+read_line:              ; rb+2 buffer, returns the length without newline
+  add #0, #0, rb+1
+next:
+  in rb+4
+  eq rb+4, #10, rb+3
+  jt rb+3, #end
+  add rb+2, rb+1, store+3
+store:
+  add rb+4, #0, 0
+  add rb+1, #1, rb+1
+  jt #1, #next
+end:
+  jt #1, rb
+";
+
+#[derive(Debug, Clone, Default)]
+pub struct Library {
+  objects: Vec<Object>,
+}
+
+impl Library {
+  pub fn new() -> Library {
+    Library::default()
+  }
+  pub fn standard() -> Library {
+    let mut library = Library::new();
+    for (name, source) in &[("print_number", PRINT_NUMBER), ("read_line", READ_LINE)] {
+      library.add(assemble(name, source).expect("standard library should assemble"));
+    }
+    library
+  }
+  pub fn add(&mut self, object: Object) {
+    self.objects.push(object);
+  }
+  pub fn find(&self, symbol: &str) -> Option<&Object> {
+    self
+      .objects
+      .iter()
+      .find(|object| object.exports().any(|name| name == symbol))
+  }
+  pub fn len(&self) -> usize {
+    self.objects.len()
+  }
+  pub fn is_empty(&self) -> bool {
+    self.objects.is_empty()
+  }
+}
+
+pub fn multiply_by(factor: i64) -> Object {
+  let name = if factor < 0 {
+    format!("mul_neg{}", factor.unsigned_abs())
+  } else {
+    format!("mul_{}", factor)
+  };
+  let source = format!(
+    ".global {name}\n{name}:\n  mul rb+1, #{factor}, rb+1\n  jt #1, rb\n",
+    name = name,
+    factor = factor
+  );
+  assemble(&name, &source).expect("multiply routine should assemble")
+}
@@ -0,0 +1,77 @@
+use super::assembler::Object;
+use super::library::Library;
+use super::Error;
+use std::collections::BTreeMap;
+
+pub const END: &str = "__end";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+  program: Vec<i64>,
+  symbols: BTreeMap<String, i64>,
+  modules: Vec<(String, i64)>,
+}
+
+impl Image {
+  pub fn program(&self) -> &[i64] {
+    &self.program
+  }
+  pub fn symbol(&self, name: &str) -> Option<i64> {
+    self.symbols.get(name).cloned()
+  }
+  pub fn modules(&self) -> &[(String, i64)] {
+    &self.modules
+  }
+}
+
+pub fn link(objects: &[Object], library: &Library) -> Result<Image, Error> {
+  let mut modules: Vec<&Object> = objects.iter().collect();
+  loop {
+    let defined: Vec<&str> = modules.iter().flat_map(|object| object.exports()).collect();
+    let missing = modules
+      .iter()
+      .flat_map(|object| object.imports())
+      .find(|name| *name != END && !defined.contains(name));
+    let name = match missing {
+      Some(name) => name,
+      None => break,
+    };
+    match library.find(name) {
+      Some(object) => modules.push(object),
+      None => return Err(Error::Undefined(name.to_owned())),
+    }
+  }
+
+  let mut symbols = BTreeMap::new();
+  let mut layout = Vec::new();
+  let mut base = 0;
+  for object in &modules {
+    layout.push((object.name().to_owned(), base));
+    for (name, offset) in &object.exports {
+      if symbols.insert(name.clone(), base + offset).is_some() {
+        return Err(Error::Duplicate(name.clone()));
+      }
+    }
+    base += object.len() as i64;
+  }
+  if symbols.insert(END.to_owned(), base).is_some() {
+    return Err(Error::Duplicate(END.to_owned()));
+  }
+
+  let mut program = Vec::with_capacity(base as usize);
+  for (object, (_, base)) in modules.iter().zip(&layout) {
+    let start = program.len();
+    program.extend(&object.code);
+    for at in &object.relocations {
+      program[start + at] += base;
+    }
+    for (at, name) in &object.references {
+      program[start + at] += symbols[name];
+    }
+  }
+  Ok(Image {
+    program,
+    symbols,
+    modules: layout,
+  })
+}
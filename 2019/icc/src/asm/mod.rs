@@ -0,0 +1,165 @@
+mod assembler;
+mod library;
+mod linker;
+
+pub use assembler::{assemble, Object};
+pub use library::{multiply_by, Library};
+pub use linker::{link, Image, END};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+  Syntax(usize, String),
+  Name(usize, String),
+  Undefined(String),
+  Duplicate(String),
+}
+impl std::fmt::Display for Error {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Error::Syntax(line, message) => write!(fmt, "Syntax error on line {}: {}.", line, message),
+      Error::Name(line, message) => write!(fmt, "Name error on line {}: {}.", line, message),
+      Error::Undefined(name) => write!(fmt, "Undefined symbol {}.", name),
+      Error::Duplicate(name) => write!(fmt, "Symbol {} is defined twice.", name),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::state::State;
+  use super::super::{run_to_end, Computer};
+  use super::*;
+
+  fn run(image: &Image, input: &[i64]) -> Vec<i64> {
+    let mut computer = Computer::load(0, image.program());
+    run_to_end(&mut computer, input).expect("should run")
+  }
+  fn ascii(output: &[i64]) -> String {
+    output.iter().map(|c| *c as u8 as char).collect()
+  }
+
+  #[test]
+  fn assemble_1() {
+    let source = "
+      in value        ; read
+      mul value, #3, value
+      out value
+      hlt
+    value: data 0";
+    let object = assemble("triple", source).unwrap();
+    let image = link(&[object], &Library::new()).unwrap();
+    assert_eq!(image.program(), &[3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+    assert_eq!(image.symbol(END), Some(10));
+    assert_eq!(run(&image, &[14]), vec![42]);
+  }
+  #[test]
+  fn relocation() {
+    let source = "out 3\nhlt\ndata 7";
+    let objects = vec![
+      assemble("a", source).unwrap(),
+      assemble("b", source).unwrap(),
+    ];
+    let image = link(&objects, &Library::new()).unwrap();
+    assert_eq!(image.program(), &[4, 3, 99, 7, 4, 7, 99, 7]);
+    assert_eq!(image.modules(), &[("a".to_owned(), 0), ("b".to_owned(), 4)]);
+  }
+  #[test]
+  fn link_library() {
+    let source = "
+      arb #__end
+      in rb+1
+      add #back, #0, rb
+      jt #1, #mul_neg3
+    back:
+      out rb+1
+      hlt";
+    let main = assemble("main", source).unwrap();
+    let mut library = Library::new();
+    library.add(multiply_by(5));
+    library.add(multiply_by(-3));
+    let length = main.len() as i64;
+    let image = link(&[main], &library).unwrap();
+    assert_eq!(image.symbol("mul_neg3"), Some(length));
+    assert_eq!(image.symbol("mul_5"), None);
+    assert_eq!(run(&image, &[14]), vec![-42]);
+  }
+  #[test]
+  fn print_number() {
+    let source = "
+      arb #__end
+    loop:
+      in rb+1
+      add #back, #0, rb
+      jt #1, #print_number
+    back:
+      out #10
+      jt #1, #loop";
+    let main = assemble("main", source).unwrap();
+    let image = link(&[main], &Library::standard()).unwrap();
+    let mut computer = Computer::load(0, image.program());
+    let mut output = Vec::new();
+    for value in &[1234, -5, 0, 1_000_000_000_000_000_000, i64::MAX] {
+      assert_eq!(computer.run(), State::Input);
+      computer.input_value(*value);
+      while let State::Output(_, value) = computer.run() {
+        output.push(value);
+      }
+    }
+    assert_eq!(
+      ascii(&output),
+      "1234\n-5\n0\n1000000000000000000\n9223372036854775807\n"
+    );
+  }
+  #[test]
+  fn read_line() {
+    let source = "
+      arb #__end
+      add #buffer, #0, rb+2
+      add #back, #0, rb
+      jt #1, #read_line
+    back:
+      out rb+1
+      hlt
+    .global buffer
+    buffer: data 0, 0, 0, 0, 0, 0, 0, 0";
+    let main = assemble("main", source).unwrap();
+    let image = link(&[main], &Library::standard()).unwrap();
+    let input: Vec<i64> = "hello\n".bytes().map(i64::from).collect();
+    let mut computer = Computer::load(0, image.program());
+    assert_eq!(run_to_end(&mut computer, &input), Ok(vec![5]));
+    let buffer = image.symbol("buffer").unwrap();
+    let stored: Vec<i64> = (buffer..buffer + 6)
+      .map(|addr| computer.read_memory(addr).unwrap())
+      .collect();
+    assert_eq!(ascii(&stored), "hello\0");
+  }
+  #[test]
+  fn errors() {
+    assert_eq!(
+      assemble("a", "add 1, 2\n"),
+      Err(Error::Syntax(1, "add takes 3 operands, not 2".into()))
+    );
+    assert_eq!(
+      assemble("a", "hlt\n  in #4"),
+      Err(Error::Syntax(2, "'#4' can not be a destination".into()))
+    );
+    assert_eq!(
+      assemble("a", "x: hlt\nx: hlt"),
+      Err(Error::Name(2, "label x is defined twice".into()))
+    );
+    assert_eq!(
+      assemble("a", ".global y\nhlt"),
+      Err(Error::Name(1, "y is exported but not defined".into()))
+    );
+    let caller = assemble("a", "jt #1, #missing").unwrap();
+    assert_eq!(
+      link(&[caller], &Library::standard()),
+      Err(Error::Undefined("missing".into()))
+    );
+    let twice = vec![multiply_by(2), multiply_by(2)];
+    assert_eq!(
+      link(&twice, &Library::new()),
+      Err(Error::Duplicate("mul_2".into()))
+    );
+  }
+}
@@ -0,0 +1,741 @@
+use super::flow::*;
+use super::instruction::Instruction;
+use super::memory::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write as _;
+
+// A run of this many instructions found by the linear sweep is taken to be
+// code, shorter runs only if they end in a halt or an unconditional jump.
+const SWEEP_RUN: usize = 3;
+
+pub fn decompile(program: &[i64]) -> String {
+  Decompiler::new(program).render()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+  If,
+  IfElse(usize),
+  Loop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Region {
+  kind: Kind,
+  start: usize,
+  end: usize,
+}
+
+impl Region {
+  fn bodies(&self) -> Vec<(usize, usize)> {
+    match self.kind {
+      Kind::If => vec![(self.start + 1, self.end)],
+      Kind::IfElse(split) => vec![(self.start + 1, split - 1), (split, self.end)],
+      Kind::Loop => vec![(self.start, self.end - 1)],
+    }
+  }
+  fn fits_in(&self, outer: &Region) -> bool {
+    outer
+      .bodies()
+      .iter()
+      .any(|(from, to)| *from <= self.start && self.end <= *to)
+  }
+  fn disjoint(&self, other: &Region) -> bool {
+    self.end <= other.start || other.end <= self.start
+  }
+}
+
+fn jump(instruction: &Instruction) -> Option<(&Value, &Value, bool)> {
+  match instruction {
+    Instruction::JumpIfTrue((value, dest)) => Some((value, dest, true)),
+    Instruction::JumpIfFalse((value, dest)) => Some((value, dest, false)),
+    _ => None,
+  }
+}
+
+fn unconditional(instruction: &Instruction) -> bool {
+  match jump(instruction) {
+    Some((Value::Direct(value), _, when)) => (*value != 0) == when,
+    _ => false,
+  }
+}
+
+fn direct_target(instruction: &Instruction) -> Option<i64> {
+  match jump(instruction) {
+    Some((_, Value::Direct(target), _)) => Some(*target),
+    _ => None,
+  }
+}
+
+fn covered(code: &[(i64, i64)], addr: i64) -> bool {
+  code.iter().any(|(from, to)| *from <= addr && addr < *to)
+}
+
+// Whether decoding from `at` gives a believable run of instructions, used to
+// pick up code behind self-modified cells and indirect jumps.
+fn plausible(program: &[i64], code: &[(i64, i64)], at: i64) -> bool {
+  let mut pc = at;
+  let mut count = 0;
+  loop {
+    if count > 0 && covered(code, pc) {
+      return true;
+    }
+    let (instruction, length) = match decode(program, pc) {
+      Some(decoded) if pc + decoded.1 <= program.len() as i64 => decoded,
+      _ => return false,
+    };
+    let halt = matches!(instruction, Instruction::Halt);
+    if halt && program[pc as usize] != 99 {
+      return false;
+    }
+    count += 1;
+    if count >= SWEEP_RUN || halt || unconditional(&instruction) {
+      return true;
+    }
+    pc += length;
+  }
+}
+
+struct Discovery {
+  blocks: Vec<Block>,
+  entries: Vec<i64>,
+  reachable: usize,
+  written: HashSet<i64>,
+  opaque: BTreeMap<i64, (i64, bool)>,
+}
+
+// Follows the control flow from the start, then sweeps the cells it did not
+// reach for more code. Cells that stop the decoding in the middle of the code
+// are kept as opaque ranges, marked when the program writes to them.
+fn discover(program: &[i64]) -> Discovery {
+  let count = |blocks: &[Block]| {
+    let cells: HashSet<i64> = blocks
+      .iter()
+      .flat_map(|block| block.body.iter().map(|(at, _)| *at))
+      .collect();
+    cells.len()
+  };
+  let mut entries = vec![0];
+  let mut blocks = blocks_from(program, &entries);
+  let reachable = count(&blocks);
+  let mut code = code_ranges(&blocks);
+  for at in 0..program.len() as i64 {
+    if !covered(&code, at) && plausible(program, &code, at) {
+      entries.push(at);
+      blocks = blocks_from(program, &entries);
+      code = code_ranges(&blocks);
+    }
+  }
+  let written: HashSet<i64> = blocks
+    .iter()
+    .flat_map(|block| block.body.iter())
+    .filter_map(|(_, instruction)| match instruction {
+      Instruction::Add((_, _, Value::Direct(addr)))
+      | Instruction::Multiply((_, _, Value::Direct(addr)))
+      | Instruction::LessThan((_, _, Value::Direct(addr)))
+      | Instruction::Equals((_, _, Value::Direct(addr)))
+      | Instruction::Input(Value::Direct(addr)) => Some(*addr),
+      _ => None,
+    })
+    .collect();
+  let falls_into: HashSet<i64> = blocks
+    .iter()
+    .flat_map(|block| block.body.iter())
+    .filter(|(_, instruction)| {
+      !matches!(instruction, Instruction::Halt) && !unconditional(instruction)
+    })
+    .map(|(at, instruction)| at + instruction.length())
+    .collect();
+  let mut opaque = BTreeMap::new();
+  for block in blocks.iter() {
+    let stop = match block.exit {
+      Exit::Stop(stop) if falls_into.contains(&stop) && !covered(&code, stop) => stop,
+      _ => continue,
+    };
+    if let Some((resume, _)) = code.iter().find(|(from, _)| *from > stop) {
+      let patched = (stop..*resume).any(|addr| written.contains(&addr));
+      opaque.insert(*resume, (stop, patched));
+    }
+  }
+  Discovery {
+    blocks,
+    entries,
+    reachable,
+    written,
+    opaque,
+  }
+}
+
+struct Decompiler<'a> {
+  program: &'a [i64],
+  listing: Vec<(i64, Instruction)>,
+  position: HashMap<i64, usize>,
+  code: Vec<(i64, i64)>,
+  reachable: usize,
+  written: HashSet<i64>,
+  opaque: BTreeMap<i64, (i64, bool)>,
+  calls: HashMap<usize, i64>,
+  stores: HashSet<usize>,
+  folded: HashSet<usize>,
+  regions: Vec<Region>,
+  labels: BTreeSet<i64>,
+  functions: BTreeSet<i64>,
+}
+
+impl<'a> Decompiler<'a> {
+  fn new(program: &'a [i64]) -> Decompiler<'a> {
+    let Discovery {
+      blocks,
+      entries,
+      reachable,
+      written,
+      opaque,
+    } = discover(program);
+    let mut code = code_ranges(&blocks);
+    code.extend(opaque.iter().map(|(resume, (stop, _))| (*stop, *resume)));
+    let mut listing: Vec<(i64, Instruction)> = blocks
+      .into_iter()
+      .flat_map(|block| block.body.into_iter())
+      .collect();
+    listing.sort_by_key(|(at, _)| *at);
+    listing.dedup_by_key(|(at, _)| *at);
+    let position = listing
+      .iter()
+      .enumerate()
+      .map(|(idx, (at, _))| (*at, idx))
+      .collect();
+    let mut decompiler = Decompiler {
+      program,
+      listing,
+      position,
+      code,
+      reachable,
+      written,
+      opaque,
+      calls: HashMap::new(),
+      stores: HashSet::new(),
+      folded: HashSet::new(),
+      regions: Vec::new(),
+      labels: entries.into_iter().skip(1).collect(),
+      functions: BTreeSet::new(),
+    };
+    decompiler.find_calls();
+    decompiler.fold_conditions();
+    decompiler.find_regions();
+    decompiler.find_labels();
+    decompiler
+  }
+
+  fn find_calls(&mut self) {
+    for idx in 1..self.listing.len() {
+      let (at, instruction) = &self.listing[idx];
+      let target = match direct_target(instruction) {
+        Some(target) if unconditional(instruction) => target,
+        _ => continue,
+      };
+      let store = match &self.listing[idx - 1].1 {
+        Instruction::ReduceAbsoluteRelative(_) if idx > 1 => idx - 2,
+        _ => idx - 1,
+      };
+      let back = match &self.listing[store].1 {
+        Instruction::Add((Value::Direct(back), Value::Direct(0), _))
+        | Instruction::Add((Value::Direct(0), Value::Direct(back), _)) => *back,
+        _ => continue,
+      };
+      if back == at + instruction.length() && self.position.contains_key(&target) {
+        self.calls.insert(idx, target);
+        self.stores.insert(store);
+        self.functions.insert(target);
+      }
+    }
+  }
+
+  fn fold_conditions(&mut self) {
+    let mut reads: HashMap<i64, usize> = HashMap::new();
+    let mut targets = HashSet::new();
+    for (_, instruction) in self.listing.iter() {
+      for value in sources(instruction) {
+        if let Value::Stored(addr) = value {
+          *reads.entry(*addr).or_insert(0) += 1;
+        }
+      }
+      if let Some(target) = direct_target(instruction) {
+        targets.insert(target);
+      }
+    }
+    for idx in 1..self.listing.len() {
+      let (at, instruction) = &self.listing[idx];
+      let flag = match jump(instruction) {
+        Some((Value::Stored(flag), _, _)) => *flag,
+        _ => continue,
+      };
+      let (prev_at, prev) = &self.listing[idx - 1];
+      let written = match prev {
+        Instruction::LessThan((_, _, Value::Direct(dest)))
+        | Instruction::Equals((_, _, Value::Direct(dest))) => *dest,
+        _ => continue,
+      };
+      if written == flag
+        && prev_at + prev.length() == *at
+        && reads.get(&flag) == Some(&1)
+        && !targets.contains(at)
+      {
+        self.folded.insert(idx - 1);
+      }
+    }
+  }
+
+  fn find_regions(&mut self) {
+    let mut candidates = Vec::new();
+    for (idx, (at, instruction)) in self.listing.iter().enumerate() {
+      let target = match direct_target(instruction) {
+        Some(target) if !self.calls.contains_key(&idx) => target,
+        _ => continue,
+      };
+      let goal = match self.position.get(&target) {
+        Some(goal) => *goal,
+        None => continue,
+      };
+      if target <= *at {
+        candidates.push(Region {
+          kind: Kind::Loop,
+          start: goal,
+          end: idx + 1,
+        });
+      } else if !unconditional(instruction) {
+        let over = &self.listing[goal - 1].1;
+        let otherwise = match direct_target(over) {
+          Some(skip) if goal - 1 > idx && unconditional(over) && skip > target => {
+            self.position.get(&skip).cloned()
+          }
+          _ => None,
+        };
+        candidates.push(match otherwise {
+          Some(end) => Region {
+            kind: Kind::IfElse(goal),
+            start: idx,
+            end,
+          },
+          None => Region {
+            kind: Kind::If,
+            start: idx,
+            end: goal,
+          },
+        });
+      }
+    }
+    candidates.sort_by_key(|region| (region.start, std::cmp::Reverse(region.end)));
+    for candidate in candidates {
+      if self.regions.iter().all(|region| {
+        candidate.disjoint(region) || candidate.fits_in(region) || region.fits_in(&candidate)
+      }) {
+        self.regions.push(candidate);
+      }
+    }
+  }
+
+  fn consumed(&self) -> HashSet<usize> {
+    let mut consumed: HashSet<usize> = self.calls.keys().cloned().collect();
+    for region in self.regions.iter() {
+      match region.kind {
+        Kind::If => consumed.insert(region.start),
+        Kind::IfElse(split) => {
+          consumed.insert(split - 1);
+          consumed.insert(region.start)
+        }
+        Kind::Loop => consumed.insert(region.end - 1),
+      };
+    }
+    consumed
+  }
+
+  fn find_labels(&mut self) {
+    let consumed = self.consumed();
+    for (idx, (_, instruction)) in self.listing.iter().enumerate() {
+      if let Some(target) = direct_target(instruction) {
+        if !consumed.contains(&idx) && self.escape(idx).is_none() {
+          self.labels.insert(target);
+        }
+      }
+    }
+  }
+
+  fn escape(&self, idx: usize) -> Option<&'static str> {
+    let target = direct_target(&self.listing[idx].1)?;
+    let innermost = self
+      .regions
+      .iter()
+      .filter(|region| region.kind == Kind::Loop && region.start <= idx && idx < region.end - 1)
+      .min_by_key(|region| region.end - region.start)?;
+    if target == self.listing[innermost.start].0 {
+      return Some("continue");
+    }
+    match self.listing.get(innermost.end) {
+      Some((after, _)) if *after == target => Some("break"),
+      _ => None,
+    }
+  }
+
+  fn name(&self, addr: i64) -> String {
+    if covered(&self.code, addr) {
+      format!("code[{}]", addr)
+    } else {
+      format!("v{}", addr)
+    }
+  }
+  fn label(&self, addr: i64) -> String {
+    if self.functions.contains(&addr) {
+      format!("f{}", addr)
+    } else {
+      format!("L{}", addr)
+    }
+  }
+  fn source(&self, value: &Value) -> String {
+    match value {
+      Value::Direct(value) => format!("{}", value),
+      Value::Stored(addr) => self.name(*addr),
+      Value::Relative(offset) => local(*offset),
+    }
+  }
+  fn destination(&self, value: &Value) -> String {
+    match value {
+      Value::Direct(addr) => self.name(*addr),
+      Value::Stored(addr) => format!("*{}", self.name(*addr)),
+      Value::Relative(offset) => local(*offset),
+    }
+  }
+
+  fn condition(&self, idx: usize, taken: bool) -> String {
+    let (value, _, when) = jump(&self.listing[idx].1).unwrap();
+    let truthy = when == taken;
+    if self.folded.contains(&idx.wrapping_sub(1)) {
+      let (op, negated, a, b) = match &self.listing[idx - 1].1 {
+        Instruction::LessThan((a, b, _)) => ("<", ">=", a, b),
+        Instruction::Equals((a, b, _)) => ("==", "!=", a, b),
+        _ => unreachable!(),
+      };
+      let op = if truthy { op } else { negated };
+      return format!("{} {} {}", self.source(a), op, self.source(b));
+    }
+    let op = if truthy { "!=" } else { "==" };
+    format!("{} {} 0", self.source(value), op)
+  }
+
+  fn statement(&self, idx: usize) -> Option<String> {
+    if self.folded.contains(&idx) || self.stores.contains(&idx) {
+      return None;
+    }
+    if let Some(target) = self.calls.get(&idx) {
+      return Some(format!("call {}();", self.label(*target)));
+    }
+    let (at, instruction) = &self.listing[idx];
+    let statement = match instruction {
+      Instruction::Add((a, b, dest)) => {
+        let value = match (a, b) {
+          (a, Value::Direct(0)) | (Value::Direct(0), a) => self.source(a),
+          (a, Value::Direct(b)) if *b < 0 => format!("{} - {}", self.source(a), -b),
+          (a, b) => format!("{} + {}", self.source(a), self.source(b)),
+        };
+        format!("{} = {};", self.destination(dest), value)
+      }
+      Instruction::Multiply((a, b, dest)) => {
+        let value = match (a, b) {
+          (a, Value::Direct(1)) | (Value::Direct(1), a) => self.source(a),
+          (a, Value::Direct(-1)) | (Value::Direct(-1), a) => format!("-{}", self.source(a)),
+          (a, b) => format!("{} * {}", self.source(a), self.source(b)),
+        };
+        format!("{} = {};", self.destination(dest), value)
+      }
+      Instruction::LessThan((a, b, dest)) => format!(
+        "{} = {} < {};",
+        self.destination(dest),
+        self.source(a),
+        self.source(b)
+      ),
+      Instruction::Equals((a, b, dest)) => format!(
+        "{} = {} == {};",
+        self.destination(dest),
+        self.source(a),
+        self.source(b)
+      ),
+      Instruction::JumpIfTrue((_, dest)) | Instruction::JumpIfFalse((_, dest)) => {
+        let target = match (self.escape(idx), dest) {
+          (Some(escape), _) => escape.to_owned(),
+          (None, Value::Direct(target)) => format!("goto {}", self.label(*target)),
+          // The target operand itself is rewritten, e.g. to index a jump table.
+          (None, Value::Stored(_)) if self.written.contains(&(at + 2)) => {
+            format!("goto *code[code[{}]]", at + 2)
+          }
+          (None, dest) => format!("goto *{}", self.source(dest)),
+        };
+        if unconditional(instruction) {
+          format!("{};", target)
+        } else {
+          format!("if ({}) {};", self.condition(idx, true), target)
+        }
+      }
+      Instruction::ReduceAbsoluteRelative(Value::Direct(size)) if *size >= 0 => {
+        format!("push_frame({});", size)
+      }
+      Instruction::ReduceAbsoluteRelative(Value::Direct(size)) => {
+        format!("pop_frame({});", -size)
+      }
+      Instruction::ReduceAbsoluteRelative(value) => {
+        format!("push_frame({});", self.source(value))
+      }
+      Instruction::Input(dest) => format!("{} = input();", self.destination(dest)),
+      Instruction::Output(value) => format!("output({});", self.source(value)),
+      Instruction::Halt => "halt;".to_owned(),
+    };
+    Some(statement)
+  }
+
+  fn emit(&self, out: &mut Output, from: usize, to: usize, depth: usize) {
+    let mut idx = from;
+    while idx < to {
+      let at = self.listing[idx].0;
+      if let (false, Some((stop, patched))) = (out.labelled.contains(&idx), self.opaque.get(&at)) {
+        let why = if *patched {
+          "patched at run time"
+        } else {
+          "not decodable"
+        };
+        out.line(depth, &format!("// code[{}..{}] is {}", stop, at, why));
+      }
+      if out.labelled.insert(idx) && (self.labels.contains(&at) || self.functions.contains(&at)) {
+        out.line(depth.saturating_sub(1), &format!("{}:", self.label(at)));
+      }
+      let region = self
+        .regions
+        .iter()
+        .enumerate()
+        .find(|(id, region)| region.start == idx && !out.opened.contains(id));
+      if let Some((id, region)) = region {
+        out.opened.insert(id);
+        match region.kind {
+          Kind::Loop => {
+            let tail = region.end - 1;
+            out.line(depth, "loop {");
+            self.emit(out, idx, tail, depth + 1);
+            if unconditional(&self.listing[tail].1) {
+              out.line(depth, "}");
+            } else {
+              out.line(
+                depth,
+                &format!("}} while ({});", self.condition(tail, true)),
+              );
+            }
+          }
+          Kind::If => {
+            out.line(depth, &format!("if ({}) {{", self.condition(idx, false)));
+            self.emit(out, idx + 1, region.end, depth + 1);
+            out.line(depth, "}");
+          }
+          Kind::IfElse(split) => {
+            out.line(depth, &format!("if ({}) {{", self.condition(idx, false)));
+            self.emit(out, idx + 1, split - 1, depth + 1);
+            out.line(depth, "} else {");
+            self.emit(out, split, region.end, depth + 1);
+            out.line(depth, "}");
+          }
+        }
+        idx = region.end;
+        continue;
+      }
+      if let Some(statement) = self.statement(idx) {
+        out.line(depth, &statement);
+      }
+      idx += 1;
+    }
+  }
+
+  fn render(&self) -> String {
+    let mut out = Output {
+      text: String::new(),
+      opened: HashSet::new(),
+      labelled: HashSet::new(),
+    };
+    write!(
+      out.text,
+      "// {} cells, {} reachable instructions",
+      self.program.len(),
+      self.reachable
+    )
+    .unwrap();
+    match self.listing.len() - self.reachable {
+      0 => writeln!(out.text).unwrap(),
+      swept => writeln!(out.text, ", {} more found by sweeping", swept).unwrap(),
+    }
+    self.emit(&mut out, 0, self.listing.len(), 1);
+    let variables: BTreeSet<i64> = self
+      .listing
+      .iter()
+      .flat_map(|(_, instruction)| cells(instruction))
+      .filter(|addr| self.name(*addr).starts_with('v'))
+      .collect();
+    if !variables.is_empty() {
+      writeln!(out.text, "data:").unwrap();
+      for addr in variables {
+        let value = self.program.get(addr as usize).cloned().unwrap_or(0);
+        writeln!(out.text, "  v{} = {};", addr, value).unwrap();
+      }
+    }
+    out.text
+  }
+}
+
+struct Output {
+  text: String,
+  opened: HashSet<usize>,
+  labelled: HashSet<usize>,
+}
+
+impl Output {
+  fn line(&mut self, depth: usize, line: &str) {
+    writeln!(self.text, "{}{}", "  ".repeat(depth), line).unwrap();
+  }
+}
+
+fn local(offset: i64) -> String {
+  if offset < 0 {
+    format!("param{}", -offset)
+  } else {
+    format!("local{}", offset)
+  }
+}
+
+fn sources(instruction: &Instruction) -> Vec<&Value> {
+  match instruction {
+    Instruction::Add((a, b, dest))
+    | Instruction::Multiply((a, b, dest))
+    | Instruction::LessThan((a, b, dest))
+    | Instruction::Equals((a, b, dest)) => match dest {
+      Value::Stored(_) => vec![a, b, dest],
+      _ => vec![a, b],
+    },
+    Instruction::JumpIfTrue((a, b)) | Instruction::JumpIfFalse((a, b)) => vec![a, b],
+    Instruction::ReduceAbsoluteRelative(a) | Instruction::Output(a) => vec![a],
+    Instruction::Input(dest @ Value::Stored(_)) => vec![dest],
+    Instruction::Input(_) | Instruction::Halt => vec![],
+  }
+}
+
+fn cells(instruction: &Instruction) -> Vec<i64> {
+  let mut cells: Vec<i64> = sources(instruction)
+    .into_iter()
+    .filter_map(|value| match value {
+      Value::Stored(addr) => Some(*addr),
+      _ => None,
+    })
+    .collect();
+  match instruction {
+    Instruction::Add((_, _, Value::Direct(addr)))
+    | Instruction::Multiply((_, _, Value::Direct(addr)))
+    | Instruction::LessThan((_, _, Value::Direct(addr)))
+    | Instruction::Equals((_, _, Value::Direct(addr)))
+    | Instruction::Input(Value::Direct(addr))
+    | Instruction::Input(Value::Stored(addr)) => cells.push(*addr),
+    _ => (),
+  }
+  cells
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn straight() {
+    let program = [3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+    assert_eq!(
+      decompile(&program),
+      "// 11 cells, 4 reachable instructions
+  v9 = input();
+  v9 = v9 == v10;
+  output(v9);
+  halt;
+data:
+  v9 = -1;
+  v10 = 8;
+"
+    );
+  }
+  #[test]
+  fn if_condition() {
+    let program = [3, 12, 1007, 12, 8, 13, 1006, 13, 11, 104, 1, 99, 0, 0];
+    assert_eq!(
+      decompile(&program),
+      "// 14 cells, 5 reachable instructions
+  v12 = input();
+  if (v12 < 8) {
+    output(1);
+  }
+  halt;
+data:
+  v12 = 0;
+  v13 = 0;
+"
+    );
+  }
+  #[test]
+  fn loop_condition() {
+    let source = "fn main() { var i = 0; while (i < 3) { output(i); i = i + 1; } }";
+    let text = decompile(&crate::lang::compile(source).unwrap());
+    assert!(text.contains("call f10();"), "{}", text);
+    assert!(text.contains("loop {"), "{}", text);
+    assert!(text.contains(") break;"), "{}", text);
+    assert!(text.contains("push_frame(43);"), "{}", text);
+  }
+  #[test]
+  fn if_else() {
+    let source = "fn main() { if (input() == 2) { output(1); } else { output(0); } }";
+    let text = decompile(&crate::lang::compile(source).unwrap());
+    assert!(text.contains("} else {"), "{}", text);
+  }
+  fn real(day: &str) -> String {
+    let file = format!("../day/{}/input.csv", day);
+    decompile(&helpers::loader::load_integer_row_list(&file)[0])
+  }
+  fn count(text: &str, pattern: &str) -> usize {
+    text.lines().filter(|line| line.contains(pattern)).count()
+  }
+
+  #[test]
+  fn diagnostics() {
+    let text = real("05");
+    assert!(
+      text.starts_with("// 678 cells, 2 reachable instructions, 181 more found by sweeping\n")
+    );
+    assert!(text.contains(
+      "  v225 = input();\n  code[6] = v225 + code[6];\n  // code[6..10] is patched at run time\nL10:\n  output(0);\n"
+    ));
+    assert_eq!(count(&text, "output("), 11);
+    assert_eq!(count(&text, "L99999"), 12);
+    assert_eq!(count(&text, "if (v224 "), 24);
+    assert!(!text.contains("v6 ="), "{}", text);
+  }
+  #[test]
+  fn amplifiers() {
+    let text = real("07");
+    assert!(
+      text.starts_with("// 523 cells, 3 reachable instructions, 188 more found by sweeping\n")
+    );
+    assert!(text.contains("  goto *code[code[8]];\n"));
+    for routine in &[21, 30, 55, 80, 101, 118, 199, 280, 361, 442] {
+      assert!(text.contains(&format!("L{}:\n", routine)), "{}", text);
+    }
+    assert_eq!(count(&text, "halt;"), 10);
+  }
+  #[test]
+  fn robot() {
+    let text = real("11");
+    assert!(
+      text.starts_with("// 650 cells, 197 reachable instructions, 12 more found by sweeping\n")
+    );
+    assert_eq!(count(&text, "loop {"), 4);
+    assert_eq!(count(&text, "} else {"), 1);
+    assert_eq!(count(&text, "call f"), 2);
+    assert_eq!(count(&text, "= input();"), 21);
+    assert_eq!(count(&text, "output("), 42);
+  }
+}
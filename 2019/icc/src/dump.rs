@@ -0,0 +1,254 @@
+use super::flow::{blocks, code_ranges};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+const ROW: i64 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+  Decimal,
+  Hex,
+}
+
+impl Format {
+  fn address(self, addr: i64) -> String {
+    match self {
+      Format::Decimal => format!("{:6}", addr),
+      Format::Hex if addr < 0 => format!("-{:05x}", addr.unsigned_abs()),
+      Format::Hex => format!("{:06x}", addr),
+    }
+  }
+  fn value(self, value: i64) -> String {
+    match self {
+      Format::Decimal => format!("{}", value),
+      Format::Hex if value < 0 => format!("-{:x}", value.unsigned_abs()),
+      Format::Hex => format!("{:x}", value),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+  pc: i64,
+  relative_base: i64,
+  image: i64,
+  input: Option<i64>,
+  memory: BTreeMap<i64, i64>,
+}
+
+impl Snapshot {
+  pub(crate) fn new<I>(pc: i64, relative_base: i64, image: i64, cells: I) -> Snapshot
+  where
+    I: IntoIterator<Item = (i64, i64)>,
+  {
+    Snapshot {
+      pc,
+      relative_base,
+      image,
+      input: None,
+      memory: cells.into_iter().collect(),
+    }
+  }
+  // The cell a machine blocked on an input instruction will write to.
+  pub(crate) fn with_input(mut self, input: Option<i64>) -> Snapshot {
+    self.input = input;
+    self
+  }
+
+  pub fn pc(&self) -> i64 {
+    self.pc
+  }
+  pub fn relative_base(&self) -> i64 {
+    self.relative_base
+  }
+  pub fn image(&self) -> i64 {
+    self.image
+  }
+  pub fn input(&self) -> Option<i64> {
+    self.input
+  }
+  pub fn get(&self, addr: i64) -> Option<i64> {
+    self.memory.get(&addr).cloned()
+  }
+  pub fn program(&self) -> Vec<i64> {
+    (0..self.image)
+      .map(|addr| self.get(addr).unwrap_or(0))
+      .collect()
+  }
+  pub fn cells(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+    self.memory.iter().map(|(addr, value)| (*addr, *value))
+  }
+
+  pub fn dump(&self, format: Format) -> String {
+    let width = self
+      .memory
+      .values()
+      .map(|value| format.value(*value).len())
+      .max()
+      .unwrap_or(1);
+    let rows: BTreeSet<i64> = self.memory.keys().map(|addr| addr.div_euclid(ROW)).collect();
+    let mut out = String::new();
+    let mut last = None;
+    for row in rows {
+      if last.is_some() && last != Some(row - 1) {
+        writeln!(out, "{:>6}", "...").unwrap();
+      }
+      last = Some(row);
+      write!(out, "{}:", format.address(row * ROW)).unwrap();
+      for addr in row * ROW..(row + 1) * ROW {
+        let cell = match self.get(addr) {
+          Some(value) => format.value(value),
+          None => ".".to_owned(),
+        };
+        write!(out, " {:>width$}", cell, width = width).unwrap();
+      }
+      writeln!(out).unwrap();
+    }
+    out
+  }
+
+  pub fn diff(&self, after: &Snapshot) -> Diff {
+    let code = code_ranges(&blocks(&self.program()));
+    let is_code = |addr: i64| code.iter().any(|(from, to)| *from <= addr && addr < *to);
+    let addresses: BTreeSet<i64> = self
+      .memory
+      .keys()
+      .chain(after.memory.keys())
+      .cloned()
+      .collect();
+    let mut diff = Diff::default();
+    for addr in addresses {
+      let old = self.get(addr);
+      let new = after.get(addr);
+      if old == new {
+        continue;
+      }
+      match (old, new) {
+        (None, Some(value)) => diff.created.push((addr, value)),
+        (old, new) => {
+          let change = (addr, old.unwrap_or(0), new.unwrap_or(0));
+          if addr >= self.image || addr < 0 {
+            diff.created.push((addr, change.2));
+          } else if is_code(addr) {
+            diff.code.push(change);
+          } else {
+            diff.changed.push(change);
+          }
+        }
+      }
+    }
+    diff
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+  pub changed: Vec<(i64, i64, i64)>,
+  pub created: Vec<(i64, i64)>,
+  pub code: Vec<(i64, i64, i64)>,
+}
+
+impl Diff {
+  pub fn is_empty(&self) -> bool {
+    self.changed.is_empty() && self.created.is_empty() && self.code.is_empty()
+  }
+
+  pub fn show(&self, format: Format) -> String {
+    let mut out = String::new();
+    for (addr, old, new) in self.changed.iter() {
+      writeln!(
+        out,
+        "changed {}: {} -> {}",
+        format.address(*addr).trim(),
+        format.value(*old),
+        format.value(*new)
+      )
+      .unwrap();
+    }
+    for (addr, value) in self.created.iter() {
+      writeln!(
+        out,
+        "created {}: {}",
+        format.address(*addr).trim(),
+        format.value(*value)
+      )
+      .unwrap();
+    }
+    for (addr, old, new) in self.code.iter() {
+      writeln!(
+        out,
+        "code    {}: {} -> {}",
+        format.address(*addr).trim(),
+        format.value(*old),
+        format.value(*new)
+      )
+      .unwrap();
+    }
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::*;
+  use super::*;
+
+  #[test]
+  fn dump_1() {
+    let computer = Computer::load(0, &[1, 0, 0, 3, 99, -1]);
+    assert_eq!(
+      computer.snapshot().dump(Format::Decimal),
+      "     0:  1  0  0  3 99 -1  .  .\n"
+    );
+    assert_eq!(
+      computer.snapshot().dump(Format::Hex),
+      "000000:  1  0  0  3 63 -1  .  .\n"
+    );
+  }
+  #[test]
+  fn dump_gap() {
+    let mut computer = Computer::load(0, &[99]);
+    computer.edit_memory(17, 5);
+    assert_eq!(
+      computer.snapshot().dump(Format::Decimal),
+      "     0: 99  .  .  .  .  .  .  .\n   ...\n    16:  .  5  .  .  .  .  .  .\n"
+    );
+  }
+  #[test]
+  fn dump_negative() {
+    let mut computer = Computer::load(0, &[99]);
+    computer.edit_memory(-3, -26);
+    assert_eq!(
+      computer.snapshot().dump(Format::Hex),
+      "-00008:   .   .   .   .   . -1a   .   .\n000000:  63   .   .   .   .   .   .   .\n"
+    );
+    let mut after = computer.clone();
+    after.edit_memory(-20, 1);
+    assert_eq!(
+      computer.snapshot().diff(&after.snapshot()).show(Format::Hex),
+      "created -00014: 1\n"
+    );
+  }
+  #[test]
+  fn diff_1() {
+    let program = [1101, 2, 3, 9, 99, 0, 0, 0, 0, 0];
+    let mut computer = Computer::load(0, &program);
+    let before = computer.snapshot();
+    computer.edit_memory(1, 7);
+    computer.edit_memory(100, 8);
+    assert_eq!(run_to_end(&mut computer, &[]), Ok(vec![]));
+    let diff = before.diff(&computer.snapshot());
+    assert_eq!(diff.changed, vec![(9, 0, 10)]);
+    assert_eq!(diff.created, vec![(100, 8)]);
+    assert_eq!(diff.code, vec![(1, 2, 7)]);
+    assert_eq!(
+      diff.show(Format::Decimal),
+      "changed 9: 0 -> 10\ncreated 100: 8\ncode    1: 2 -> 7\n"
+    );
+  }
+  #[test]
+  fn diff_empty() {
+    let computer = Computer::load(0, &[104, 1, 99]);
+    assert!(computer.snapshot().diff(&computer.snapshot()).is_empty());
+  }
+}
@@ -0,0 +1,103 @@
+use super::error::Error;
+use super::state::State;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+  Output { pc: i64, value: i64 },
+  Input { pc: i64, destination: i64 },
+  Halted { pc: i64 },
+  Faulted { pc: i64, error: Error },
+  Breakpoint { pc: i64 },
+  BudgetExhausted { pc: i64 },
+}
+
+impl Event {
+  pub fn pc(&self) -> i64 {
+    match self {
+      Event::Output { pc, .. }
+      | Event::Input { pc, .. }
+      | Event::Halted { pc }
+      | Event::Faulted { pc, .. }
+      | Event::Breakpoint { pc }
+      | Event::BudgetExhausted { pc } => *pc,
+    }
+  }
+  pub fn to_state(&self, id: usize) -> State {
+    match self {
+      Event::Output { value, .. } => State::Output(id, *value),
+      Event::Input { .. } => State::Input,
+      Event::Halted { .. } => State::Halted,
+      Event::Faulted { .. } => State::Crashed,
+      Event::Breakpoint { .. } | Event::BudgetExhausted { .. } => State::Running,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::*;
+  use super::*;
+  const ID: usize = 0;
+
+  #[test]
+  fn events_1() {
+    let program = vec![109, 5, 203, 2, 204, 2, 99];
+    let mut computer = Computer::load(ID, &program);
+    assert_eq!(
+      computer.resume(),
+      Event::Input {
+        pc: 2,
+        destination: 7
+      }
+    );
+    assert_eq!(
+      computer.resume(),
+      Event::Input {
+        pc: 2,
+        destination: 7
+      }
+    );
+    assert!(computer.input_value(42));
+    assert_eq!(computer.resume(), Event::Output { pc: 4, value: 42 });
+    assert_eq!(computer.resume(), Event::Halted { pc: 6 });
+    assert_eq!(computer.resume(), Event::Halted { pc: 6 });
+  }
+  #[test]
+  fn events_fault() {
+    let program = vec![1101, 0, 0, 4, 5];
+    let mut computer = Computer::load(ID, &program);
+    assert_eq!(
+      computer.resume(),
+      Event::Faulted {
+        pc: 4,
+        error: Error::Operand(0)
+      }
+    );
+    let program = vec![4, -3, 99];
+    let mut computer = Computer::load(ID, &program);
+    let fault = Event::Faulted {
+      pc: 0,
+      error: Error::Address(-3),
+    };
+    assert_eq!(computer.resume(), fault);
+    assert_eq!(computer.resume(), fault);
+  }
+  #[test]
+  fn events_breakpoint() {
+    let program = vec![1001, 7, 1, 7, 1105, 1, 0, 0];
+    let mut computer = Computer::load(ID, &program);
+    computer.add_breakpoint(4);
+    assert_eq!(computer.resume(), Event::Breakpoint { pc: 4 });
+    assert_eq!(computer.resume(), Event::Breakpoint { pc: 4 });
+    assert_eq!(computer.read_memory(7), Some(2));
+    assert!(computer.remove_breakpoint(4));
+    assert_eq!(computer.resume_for(10), Event::BudgetExhausted { pc: 4 });
+    assert_eq!(computer.read_memory(7), Some(7));
+  }
+  #[test]
+  fn events_compat() {
+    let mut computer = Computer::load(3, &[104, 9, 99]);
+    assert_eq!(computer.resume().to_state(3), State::Output(3, 9));
+    assert_eq!(computer.run(), State::Halted);
+  }
+}
@@ -0,0 +1,142 @@
+use super::instruction::Instruction;
+use super::memory::Value;
+use std::collections::BTreeSet;
+
+pub fn decode(program: &[i64], pc: i64) -> Option<(Instruction, i64)> {
+  let fetch = |addr: i64| {
+    if addr < 0 {
+      None
+    } else {
+      program.get(addr as usize).cloned()
+    }
+  };
+  Instruction::decode(fetch, pc).ok()
+}
+
+pub fn leaders(program: &[i64], entries: &[i64]) -> BTreeSet<i64> {
+  let mut leaders: BTreeSet<i64> = entries.iter().cloned().collect();
+  let mut todo = entries.to_vec();
+  let mut seen = BTreeSet::new();
+  while let Some(pc) = todo.pop() {
+    if !seen.insert(pc) {
+      continue;
+    }
+    if let Some((instruction, length)) = decode(program, pc) {
+      let next = pc + length;
+      match instruction {
+        Instruction::Halt => (),
+        Instruction::JumpIfTrue((_, dest)) | Instruction::JumpIfFalse((_, dest)) => {
+          if let Value::Direct(dest) = dest {
+            leaders.insert(dest);
+            todo.push(dest);
+          }
+          leaders.insert(next);
+          todo.push(next);
+        }
+        Instruction::Input(_) | Instruction::Output(_) => {
+          leaders.insert(next);
+          todo.push(next);
+        }
+        _ => todo.push(next),
+      }
+    }
+  }
+  leaders
+}
+
+pub fn code_ranges(blocks: &[Block]) -> Vec<(i64, i64)> {
+  let mut cells: Vec<(i64, i64)> = blocks
+    .iter()
+    .flat_map(|block| block.body.iter().map(|(at, i)| (*at, at + i.length())))
+    .collect();
+  cells.sort();
+  cells.iter().fold(Vec::new(), |mut ranges, (from, to)| {
+    match ranges.last_mut() {
+      Some((_, end)) if *end >= *from => *end = std::cmp::max(*end, *to),
+      _ => ranges.push((*from, *to)),
+    }
+    ranges
+  })
+}
+
+pub enum Exit {
+  Fallthrough(i64),
+  Stop(i64),
+}
+
+pub struct Block {
+  pub leader: i64,
+  pub body: Vec<(i64, Instruction)>,
+  pub exit: Exit,
+}
+
+impl Block {
+  pub fn build(program: &[i64], leader: i64, leaders: &BTreeSet<i64>) -> Block {
+    let mut body = Vec::new();
+    let mut pc = leader;
+    let exit = loop {
+      if pc != leader && leaders.contains(&pc) {
+        break Exit::Fallthrough(pc);
+      }
+      match decode(program, pc) {
+        Some((instruction, length)) => {
+          let terminal = matches!(
+            instruction,
+            Instruction::Halt
+              | Instruction::JumpIfTrue(_)
+              | Instruction::JumpIfFalse(_)
+              | Instruction::Input(_)
+              | Instruction::Output(_)
+          );
+          body.push((pc, instruction));
+          pc += length;
+          if terminal {
+            break Exit::Fallthrough(pc);
+          }
+        }
+        None => break Exit::Stop(pc),
+      }
+    };
+    Block { leader, body, exit }
+  }
+}
+
+pub fn blocks(program: &[i64]) -> Vec<Block> {
+  blocks_from(program, &[0])
+}
+
+pub fn blocks_from(program: &[i64], entries: &[i64]) -> Vec<Block> {
+  let leaders = leaders(program, entries);
+  leaders
+    .iter()
+    .map(|leader| Block::build(program, *leader, &leaders))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn leaders_1() {
+    let program = [3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+    assert_eq!(
+      leaders(&program, &[0]).into_iter().collect::<Vec<_>>(),
+      vec![0, 2, 8]
+    );
+  }
+  #[test]
+  fn leaders_2() {
+    let program = [3, 12, 1006, 12, 9, 1101, 0, 1, 13, 4, 13, 99, -1, 0];
+    assert_eq!(
+      leaders(&program, &[0]).into_iter().collect::<Vec<_>>(),
+      vec![0, 2, 5, 9, 11]
+    );
+  }
+  #[test]
+  fn code_1() {
+    let program = [1, 0, 0, 0, 99, 7, 7];
+    let blocks = vec![Block::build(&program, 0, &leaders(&program, &[0]))];
+    assert_eq!(code_ranges(&blocks), vec![(0, 5)]);
+  }
+}
@@ -0,0 +1,429 @@
+use super::state::State;
+use super::Computer;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, ToSocketAddrs};
+use tracing::{debug, info};
+
+// Every Intcode cell is presented as eight little endian bytes, so gdb
+// addresses (including pc and relative_base) are cell addresses times eight.
+const CELL: i64 = 8;
+// Largest m/M transfer in bytes, two hex digits each fill the packet size.
+const TRANSFER: i64 = 0x800;
+const TARGET: &str = "<?xml version=\"1.0\"?><!DOCTYPE target SYSTEM \"gdb-target.dtd\"><target><feature name=\"org.intcode.core\"><reg name=\"pc\" bitsize=\"64\" type=\"code_ptr\" regnum=\"0\"/><reg name=\"rb\" bitsize=\"64\" type=\"data_ptr\" regnum=\"1\"/></feature></target>";
+
+pub struct Stub {
+  computer: Computer,
+  input: VecDeque<i64>,
+  output: Vec<i64>,
+}
+
+impl Stub {
+  pub fn new(computer: Computer) -> Stub {
+    Stub {
+      computer,
+      input: VecDeque::new(),
+      output: Vec::new(),
+    }
+  }
+  pub fn with_input(mut self, input: &[i64]) -> Stub {
+    self.input.extend(input);
+    self
+  }
+  pub fn computer(&self) -> &Computer {
+    &self.computer
+  }
+  pub fn output(&self) -> &[i64] {
+    &self.output
+  }
+
+  pub fn listen<A: ToSocketAddrs>(&mut self, addr: A) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    let (mut stream, peer) = listener.accept()?;
+    info!(machine = self.computer.id, %peer, "gdb attached");
+    self.serve(&mut stream)
+  }
+
+  pub fn serve<S: Read + Write>(&mut self, stream: &mut S) -> io::Result<()> {
+    while let Some(packet) = receive(stream)? {
+      let (reply, done) = self.handle(&packet, stream)?;
+      debug!(machine = self.computer.id, %packet, %reply, "gdb packet");
+      send(stream, &reply)?;
+      if done {
+        break;
+      }
+    }
+    Ok(())
+  }
+
+  fn handle<S: Write>(&mut self, packet: &str, stream: &mut S) -> io::Result<(String, bool)> {
+    let (command, args) = packet.split_at(packet.chars().next().map_or(0, char::len_utf8));
+    let reply = match command {
+      "?" => self.stop_reason(),
+      "g" => format!(
+        "{}{}",
+        encode(self.computer.pc * CELL),
+        encode(self.computer.relative_base * CELL)
+      ),
+      "G" => match (decode(args.get(..16)), decode(args.get(16..32))) {
+        (Some(pc), Some(rb)) => {
+          self.computer.pc = pc / CELL;
+          self.computer.relative_base = rb / CELL;
+          "OK".to_owned()
+        }
+        _ => "E01".to_owned(),
+      },
+      "p" => match i64::from_str_radix(args, 16) {
+        Ok(0) => encode(self.computer.pc * CELL),
+        Ok(1) => encode(self.computer.relative_base * CELL),
+        _ => "E01".to_owned(),
+      },
+      "P" => match parse_register(args) {
+        Some((0, value)) => {
+          self.computer.pc = value / CELL;
+          "OK".to_owned()
+        }
+        Some((1, value)) => {
+          self.computer.relative_base = value / CELL;
+          "OK".to_owned()
+        }
+        _ => "E01".to_owned(),
+      },
+      "m" => match memory_range(args) {
+        Some((addr, len)) => (addr..addr + len)
+          .map(|byte| format!("{:02x}", self.byte(byte)))
+          .collect(),
+        _ => "E01".to_owned(),
+      },
+      "M" => match args.find(':').and_then(|at| {
+        let (range, data) = args.split_at(at);
+        memory_range(range).map(|range| (range, &data[1..]))
+      }) {
+        Some(((addr, len), data)) if data.len() as i64 == 2 * len => match hex_bytes(data) {
+          Some(bytes) => {
+            for (idx, byte) in bytes.into_iter().enumerate() {
+              self.set_byte(addr + idx as i64, byte);
+            }
+            "OK".to_owned()
+          }
+          None => "E01".to_owned(),
+        },
+        _ => "E01".to_owned(),
+      },
+      "Z" | "z" => match args.split(',').collect::<Vec<_>>().as_slice() {
+        ["0", addr, _] | ["1", addr, _] => match i64::from_str_radix(addr, 16) {
+          Ok(addr) => {
+            if command == "Z" {
+              self.computer.add_breakpoint(addr / CELL);
+            } else {
+              self.computer.remove_breakpoint(addr / CELL);
+            }
+            "OK".to_owned()
+          }
+          Err(_) => "E01".to_owned(),
+        },
+        _ => String::new(),
+      },
+      "s" => self.resume(true, stream)?,
+      "c" => self.resume(false, stream)?,
+      "H" | "T" => "OK".to_owned(),
+      "D" => return Ok(("OK".to_owned(), true)),
+      "k" => return Ok((String::new(), true)),
+      "q" => self.query(args),
+      _ => String::new(),
+    };
+    Ok((reply, false))
+  }
+
+  fn query(&mut self, args: &str) -> String {
+    if args.starts_with("Supported") {
+      "PacketSize=1000;qXfer:features:read+".to_owned()
+    } else if args == "Attached" {
+      "1".to_owned()
+    } else if args == "C" {
+      format!("QC{:x}", self.computer.id + 1)
+    } else if let Some(range) = args.strip_prefix("Xfer:features:read:target.xml:") {
+      match parse_range(range) {
+        Some((offset, len)) => {
+          let offset = std::cmp::min(offset as usize, TARGET.len());
+          let end = std::cmp::min(offset + len as usize, TARGET.len());
+          let kind = if end == TARGET.len() { "l" } else { "m" };
+          format!("{}{}", kind, &TARGET[offset..end])
+        }
+        None => "E01".to_owned(),
+      }
+    } else if let Some(command) = args.strip_prefix("Rcmd,") {
+      let command: String = match hex_bytes(command) {
+        Some(bytes) => bytes.into_iter().map(char::from).collect(),
+        None => return "E01".to_owned(),
+      };
+      let mut words = command.split_whitespace();
+      match (words.next(), words.next().map(str::parse::<i64>)) {
+        (Some("input"), Some(Ok(value))) => {
+          self.input.push_back(value);
+          "OK".to_owned()
+        }
+        _ => "E01".to_owned(),
+      }
+    } else {
+      String::new()
+    }
+  }
+
+  fn stop_reason(&self) -> String {
+    match self.computer.state {
+      State::Halted => "W00".to_owned(),
+      State::Crashed => "X0b".to_owned(),
+      _ => "S05".to_owned(),
+    }
+  }
+
+  fn resume<S: Write>(&mut self, single: bool, stream: &mut S) -> io::Result<String> {
+    let mut first = true;
+    loop {
+      if !first && self.computer.breakpoints.contains(&self.computer.pc) {
+        return Ok("S05".to_owned());
+      }
+      first = false;
+      if self.computer.want_input().is_some() {
+        match self.input.pop_front() {
+          Some(value) => {
+            self.computer.input_value(value);
+          }
+          None => return Ok("S05".to_owned()),
+        }
+        if single {
+          return Ok(self.stop_reason());
+        }
+        continue;
+      }
+      match self.computer.step() {
+        State::Halted | State::Crashed => return Ok(self.stop_reason()),
+        State::Output(_, value) => {
+          self.output.push(value);
+          let text: String = format!("{}\n", value)
+            .bytes()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+          send(stream, &format!("O{}", text))?;
+        }
+        _ => (),
+      }
+      if single {
+        return Ok(self.stop_reason());
+      }
+    }
+  }
+
+  fn byte(&self, byte: i64) -> u8 {
+    let cell = self.computer.read_memory(byte / CELL).unwrap_or(0);
+    cell.to_le_bytes()[(byte % CELL) as usize]
+  }
+  fn set_byte(&mut self, byte: i64, value: u8) {
+    let addr = byte / CELL;
+    let mut bytes = self.computer.read_memory(addr).unwrap_or(0).to_le_bytes();
+    bytes[(byte % CELL) as usize] = value;
+    self.computer.edit_memory(addr, i64::from_le_bytes(bytes));
+  }
+}
+
+fn encode(value: i64) -> String {
+  value
+    .to_le_bytes()
+    .iter()
+    .fold(String::new(), |mut out, byte| {
+      write!(out, "{:02x}", byte).unwrap();
+      out
+    })
+}
+
+fn decode(hex: Option<&str>) -> Option<i64> {
+  let hex = hex?;
+  let mut bytes = [0; 8];
+  for (idx, byte) in bytes.iter_mut().enumerate() {
+    *byte = u8::from_str_radix(hex.get(2 * idx..2 * idx + 2)?, 16).ok()?;
+  }
+  Some(i64::from_le_bytes(bytes))
+}
+
+// Works on bytes, a packet may carry anything the client sent.
+fn hex_bytes(hex: &str) -> Option<Vec<u8>> {
+  let digit = |byte: u8| char::from(byte).to_digit(16);
+  hex
+    .as_bytes()
+    .chunks(2)
+    .map(|pair| match pair {
+      [high, low] => Some((digit(*high)? * 16 + digit(*low)?) as u8),
+      _ => None,
+    })
+    .collect()
+}
+
+fn parse_register(args: &str) -> Option<(i64, i64)> {
+  let mut parts = args.splitn(2, '=');
+  let register = i64::from_str_radix(parts.next()?, 16).ok()?;
+  Some((register, decode(parts.next())?))
+}
+
+fn parse_range(args: &str) -> Option<(i64, i64)> {
+  let mut parts = args.splitn(2, ',');
+  let addr = i64::from_str_radix(parts.next()?, 16).ok()?;
+  let len = i64::from_str_radix(parts.next()?, 16).ok()?;
+  Some((addr, len))
+}
+
+fn memory_range(args: &str) -> Option<(i64, i64)> {
+  let (addr, len) = parse_range(args)?;
+  addr.checked_add(len)?;
+  if addr < 0 || !(0..=TRANSFER).contains(&len) {
+    return None;
+  }
+  Some((addr, len))
+}
+
+fn checksum(data: &str) -> u8 {
+  data.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte))
+}
+
+fn send<S: Write>(stream: &mut S, data: &str) -> io::Result<()> {
+  write!(stream, "${}#{:02x}", data, checksum(data))?;
+  stream.flush()
+}
+
+fn receive<S: Read + Write>(stream: &mut S) -> io::Result<Option<String>> {
+  let mut byte = [0; 1];
+  loop {
+    if stream.read(&mut byte)? == 0 {
+      return Ok(None);
+    }
+    if byte[0] != b'$' {
+      continue;
+    }
+    let mut data = Vec::new();
+    loop {
+      if stream.read(&mut byte)? == 0 {
+        return Ok(None);
+      }
+      if byte[0] == b'#' {
+        break;
+      }
+      data.push(byte[0]);
+    }
+    let mut sum = [0; 2];
+    stream.read_exact(&mut sum)?;
+    let data = String::from_utf8_lossy(&data).into_owned();
+    let expected = std::str::from_utf8(&sum)
+      .ok()
+      .and_then(|sum| u8::from_str_radix(sum, 16).ok());
+    if expected == Some(checksum(&data)) {
+      stream.write_all(b"+")?;
+      return Ok(Some(data));
+    }
+    stream.write_all(b"-")?;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::net::TcpStream;
+  use std::thread;
+
+  struct Client {
+    stream: TcpStream,
+  }
+
+  impl Client {
+    fn ask(&mut self, data: &str) -> String {
+      send(&mut self.stream, data).unwrap();
+      let mut ack = [0; 1];
+      self.stream.read_exact(&mut ack).unwrap();
+      assert_eq!(&ack, b"+");
+      self.reply()
+    }
+    fn reply(&mut self) -> String {
+      receive(&mut self.stream).unwrap().unwrap()
+    }
+  }
+
+  fn attach(program: &[i64], input: &[i64]) -> (Client, thread::JoinHandle<Stub>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut stub = Stub::new(Computer::load(0, program)).with_input(input);
+    let server = thread::spawn(move || {
+      let (mut stream, _) = listener.accept().unwrap();
+      stub.serve(&mut stream).unwrap();
+      stub
+    });
+    let stream = TcpStream::connect(addr).unwrap();
+    (Client { stream }, server)
+  }
+
+  #[test]
+  fn registers_and_memory() {
+    let (mut client, server) = attach(&[109, 7, 99], &[]);
+    assert_eq!(client.ask("?"), "S05");
+    assert_eq!(client.ask("m0,10"), "6d000000000000000700000000000000");
+    assert_eq!(client.ask("s"), "S05");
+    assert_eq!(client.ask("g"), "10000000000000003800000000000000");
+    assert_eq!(client.ask("M8,8:0900000000000000"), "OK");
+    assert_eq!(client.ask("p1"), "3800000000000000");
+    assert_eq!(client.ask("P0=0000000000000000"), "OK");
+    assert_eq!(client.ask("s"), "S05");
+    assert_eq!(client.ask("p1"), "8000000000000000");
+    assert_eq!(client.ask("c"), "W00");
+    assert_eq!(client.ask("Z0,10,1"), "OK");
+    assert_eq!(client.ask("D"), "OK");
+    let stub = server.join().unwrap();
+    assert!(stub.computer().breakpoints.contains(&2));
+    assert_eq!(stub.computer().read_memory(1), Some(9));
+    assert_eq!(stub.computer().relative_base(), 16);
+  }
+  #[test]
+  fn memory_out_of_range() {
+    let (mut client, server) = attach(&[99], &[]);
+    assert_eq!(client.ask("m ffffffffffffffff,10"), "E01");
+    assert_eq!(client.ask("mffffffffffffffff,10"), "E01");
+    assert_eq!(client.ask("m7ffffffffffffff8,10"), "E01");
+    assert_eq!(client.ask("m0,7fffffffffffffff"), "E01");
+    assert_eq!(client.ask("m0,801"), "E01");
+    assert_eq!(client.ask("M7ffffffffffffff8,10:00"), "E01");
+    assert_eq!(client.ask("M0,2:aé0"), "E01");
+    assert_eq!(client.ask("M0,1:+1"), "E01");
+    assert_eq!(client.ask("qRcmd,aé"), "E01");
+    assert_eq!(client.ask("qRcmd,696e707574"), "E01");
+    assert_eq!(client.ask("m0,8"), "6300000000000000");
+    assert_eq!(client.ask("D"), "OK");
+    server.join().unwrap();
+  }
+  #[test]
+  fn breakpoints_and_io() {
+    let program = [3, 9, 1001, 9, 1, 9, 4, 9, 99, 0];
+    let (mut client, server) = attach(&program, &[]);
+    assert_eq!(client.ask("Z0,30,1"), "OK");
+    assert_eq!(client.ask("c"), "S05");
+    assert_eq!(client.ask("p0"), "1000000000000000");
+    assert_eq!(client.ask("qRcmd,696e707574203431"), "OK");
+    assert_eq!(client.ask("c"), "S05");
+    assert_eq!(client.ask("p0"), "3000000000000000");
+    assert_eq!(client.ask("z0,30,1"), "OK");
+    send(&mut client.stream, "c").unwrap();
+    let mut ack = [0; 1];
+    client.stream.read_exact(&mut ack).unwrap();
+    assert_eq!(client.reply(), "O34320a");
+    assert_eq!(client.reply(), "W00");
+    assert_eq!(client.ask("k"), "");
+    assert_eq!(server.join().unwrap().output(), &[42]);
+  }
+  #[test]
+  fn target_description() {
+    let (mut client, server) = attach(&[99], &[]);
+    assert!(client.ask("qSupported:xmlRegisters=i386").contains("qXfer:features:read+"));
+    let xml = client.ask("qXfer:features:read:target.xml:0,fff");
+    assert!(xml.starts_with("l<?xml"));
+    assert!(xml.contains("name=\"rb\""));
+    assert_eq!(client.ask("D"), "OK");
+    server.join().unwrap();
+  }
+}
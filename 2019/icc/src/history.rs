@@ -0,0 +1,133 @@
+use super::error::Error;
+use super::memory::Value;
+use super::state::State;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+  pub pc: i64,
+  pub relative_base: i64,
+  pub input: Option<Value>,
+  pub state: State,
+  pub error: Option<Error>,
+  pub writes: Vec<(i64, Option<i64>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct History {
+  depth: usize,
+  entries: VecDeque<Entry>,
+}
+
+impl History {
+  pub fn new(depth: usize) -> History {
+    History {
+      depth,
+      entries: VecDeque::with_capacity(depth),
+    }
+  }
+  pub fn depth(&self) -> usize {
+    self.depth
+  }
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+  pub fn push(&mut self, entry: Entry) {
+    if self.depth == 0 {
+      return;
+    }
+    if self.entries.len() == self.depth {
+      self.entries.pop_front();
+    }
+    self.entries.push_back(entry);
+  }
+  pub fn record_write(&mut self, addr: i64, old: Option<i64>) {
+    if let Some(entry) = self.entries.back_mut() {
+      entry.writes.push((addr, old));
+    }
+  }
+  pub fn pop(&mut self) -> Option<Entry> {
+    self.entries.pop_back()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::*;
+  const ID: usize = 0;
+
+  #[test]
+  fn step_back_1() {
+    let program = vec![1, 0, 0, 0, 99];
+    let mut computer = Computer::load(ID, &program);
+    computer.keep_history(10);
+    assert!(run_to_end(&mut computer, &[]).is_ok());
+    assert_eq!(computer.core(), vec![2, 0, 0, 0, 99]);
+    assert!(computer.step_back());
+    assert_eq!(computer.pc(), 4);
+    assert!(computer.step_back());
+    assert_eq!(computer.pc(), 0);
+    assert_eq!(computer.core(), program);
+    assert!(!computer.step_back());
+  }
+  #[test]
+  fn step_back_input() {
+    let program = vec![3, 0, 4, 0, 99];
+    let mut computer = Computer::load(ID, &program);
+    computer.keep_history(10);
+    assert_eq!(run_to_end(&mut computer, &[42]), Ok(vec![42]));
+    assert!(computer.step_back());
+    assert!(computer.step_back());
+    assert_eq!(computer.core(), vec![42, 0, 4, 0, 99]);
+    assert!(computer.step_back());
+    assert_eq!(computer.core(), program);
+    assert_eq!(computer.want_input(), Some(ID));
+    assert!(computer.step_back());
+    assert_eq!(computer.want_input(), None);
+    assert_eq!(run_to_end(&mut computer, &[7]), Ok(vec![7]));
+  }
+  #[test]
+  fn step_back_relative() {
+    let program = vec![109, 19, 99];
+    let mut computer = Computer::load(ID, &program);
+    computer.keep_history(10);
+    assert!(run_to_end(&mut computer, &[]).is_ok());
+    assert_eq!(computer.relative_base(), 19);
+    assert!(computer.step_back());
+    assert!(computer.step_back());
+    assert_eq!(computer.relative_base(), 0);
+  }
+  #[test]
+  fn step_back_crash() {
+    let program = vec![1101, 50, 0, 4, 0];
+    let mut computer = Computer::load(ID, &program);
+    computer.keep_history(10);
+    assert!(run_to_end(&mut computer, &[]).is_err());
+    assert!(computer.step_back());
+    assert_eq!(computer.core(), vec![1101, 50, 0, 4, 50]);
+    assert!(computer.step_back());
+    assert_eq!(computer.core(), program);
+    assert_eq!(computer.state(), State::Ready);
+  }
+  #[test]
+  fn step_back_depth() {
+    let program = vec![1101, 1, 1, 0, 1101, 1, 1, 0, 1101, 1, 1, 0, 99];
+    let mut computer = Computer::load(ID, &program);
+    computer.keep_history(2);
+    assert!(run_to_end(&mut computer, &[]).is_ok());
+    assert!(computer.step_back());
+    assert!(computer.step_back());
+    assert!(!computer.step_back());
+    assert_eq!(computer.pc(), 8);
+  }
+  #[test]
+  fn step_back_edit() {
+    let program = vec![99];
+    let mut computer = Computer::load(ID, &program);
+    computer.keep_history(1);
+    computer.edit_memory(7, 3);
+    assert_eq!(computer.read_memory(7), Some(3));
+    assert!(computer.step_back());
+    assert_eq!(computer.read_memory(7), None);
+  }
+}
@@ -1,3 +1,4 @@
+use super::symbolic::{solve, Executor};
 use std::collections::HashSet;
 
 pub fn find_cent(computer: &super::Computer, target: i64) -> Option<i64> {
@@ -9,6 +10,15 @@ pub fn find_cent(computer: &super::Computer, target: i64) -> Option<i64> {
   }
   None
 }
+pub fn solve_cent(computer: &super::Computer, target: i64) -> Option<i64> {
+  let mut executor = Executor::restore(&computer.snapshot());
+  let noun = executor.symbolize(1);
+  let verb = executor.symbolize(2);
+  executor.run().ok()?;
+  let domains = [(noun, 0..=99), (verb, 0..=99)].iter().cloned().collect();
+  let values = solve(&executor.cell(0), target, &domains).ok()??;
+  Some(Input::new(values[&noun], values[&verb]).cent())
+}
 pub fn run(computer: &super::Computer, input: Option<Input>) -> i64 {
   let mut computer = computer.clone();
   if let Some(input) = input {
@@ -2,6 +2,7 @@ use super::error::Error;
 use super::memory::{Mode, Value};
 use super::opcode::OpCode;
 
+#[derive(Debug, Clone)]
 pub enum Instruction {
   Add((Value, Value, Value)),
   Multiply((Value, Value, Value)),
@@ -29,3 +30,69 @@ impl Instruction {
     ))
   }
 }
+
+impl Instruction {
+  pub fn decode<F>(fetch: F, pc: i64) -> Result<(Instruction, i64), Error>
+  where
+    F: Fn(i64) -> Option<i64>,
+  {
+    let operand = |offset: i64| -> Result<i64, Error> {
+      let address = pc + offset;
+      if address < 0 {
+        return Err(Error::Address(address));
+      }
+      Ok(fetch(address).unwrap_or(0))
+    };
+    let value = |mode: Mode, offset: i64| -> Result<Value, Error> {
+      let value = operand(offset)?;
+      match mode {
+        Mode::Direct => Ok(Value::Direct(value)),
+        Mode::Stored => Ok(Value::Stored(value)),
+        Mode::Relative => Ok(Value::Relative(value)),
+      }
+    };
+    let triplet =
+      |mode0: Mode, mode1: Mode, mode_d: Mode| -> Result<(Value, Value, Value), Error> {
+        Ok((value(mode0, 1)?, value(mode1, 2)?, value(mode_d, 3)?))
+      };
+    let duplex = |mode0: Mode, mode1: Mode| -> Result<(Value, Value), Error> {
+      Ok((value(mode0, 1)?, value(mode1, 2)?))
+    };
+    let op_value = fetch(pc).ok_or(Error::Address(pc))?;
+    let instruction = match op_value {
+      1 => Instruction::Add(triplet(Mode::Stored, Mode::Stored, Mode::Direct)?),
+      2 => Instruction::Multiply(triplet(Mode::Stored, Mode::Stored, Mode::Direct)?),
+      3 => Instruction::Input(value(Mode::Direct, 1)?),
+      4 => Instruction::Output(value(Mode::Stored, 1)?),
+      99 => Instruction::Halt,
+      _ => {
+        let (code, mode0, mode1, mode_d) = Instruction::get_op_modes(op_value)?;
+        match code {
+          OpCode::Add => Instruction::Add(triplet(mode0, mode1, mode_d)?),
+          OpCode::Multiply => Instruction::Multiply(triplet(mode0, mode1, mode_d)?),
+          OpCode::LessThan => Instruction::LessThan(triplet(mode0, mode1, mode_d)?),
+          OpCode::Equals => Instruction::Equals(triplet(mode0, mode1, mode_d)?),
+          OpCode::JumpIfTrue => Instruction::JumpIfTrue(duplex(mode0, mode1)?),
+          OpCode::JumpIfFalse => Instruction::JumpIfFalse(duplex(mode0, mode1)?),
+          OpCode::Read => Instruction::Input(value(mode0, 1)?),
+          OpCode::Write => Instruction::Output(value(mode0, 1)?),
+          OpCode::ReduceAbsoluteRelative => Instruction::ReduceAbsoluteRelative(value(mode0, 1)?),
+          OpCode::Halt => Instruction::Halt, // Weird address mode for halt?
+        }
+      }
+    };
+    let length = instruction.length();
+    Ok((instruction, length))
+  }
+  pub fn length(&self) -> i64 {
+    match self {
+      Instruction::Add(_)
+      | Instruction::Multiply(_)
+      | Instruction::LessThan(_)
+      | Instruction::Equals(_) => 4,
+      Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_) => 3,
+      Instruction::ReduceAbsoluteRelative(_) | Instruction::Input(_) | Instruction::Output(_) => 2,
+      Instruction::Halt => 1,
+    }
+  }
+}
@@ -0,0 +1,508 @@
+use super::parser::{BinOp, Block, Expr, Function, Stmt};
+use super::Error;
+use std::collections::HashMap;
+
+const ADD: i64 = 1;
+const MULTIPLY: i64 = 2;
+const INPUT: i64 = 3;
+const OUTPUT: i64 = 4;
+const JUMP_IF_TRUE: i64 = 5;
+const JUMP_IF_FALSE: i64 = 6;
+const LESS_THAN: i64 = 7;
+const EQUALS: i64 = 8;
+const RELATIVE: i64 = 9;
+const HALT: i64 = 99;
+
+// Frame layout relative to the base: return address, return value,
+// parameters, locals and finally temporaries.
+const RETURN_ADDRESS: i64 = 0;
+const RETURN_VALUE: i64 = 1;
+const FIRST_PARAM: i64 = 2;
+
+#[derive(Debug, Clone, Copy)]
+enum Word {
+  Int(i64),
+  Label(usize),
+  Temp(i64),
+  Frame(i64),
+  Back,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+  Imm(Word),
+  Pos(Word),
+  Rel(Word),
+}
+
+impl Operand {
+  fn mode(&self) -> i64 {
+    match self {
+      Operand::Pos(_) => 0,
+      Operand::Imm(_) => 1,
+      Operand::Rel(_) => 2,
+    }
+  }
+  fn word(&self) -> Word {
+    match self {
+      Operand::Imm(word) | Operand::Pos(word) | Operand::Rel(word) => *word,
+    }
+  }
+}
+
+fn imm(value: i64) -> Operand {
+  Operand::Imm(Word::Int(value))
+}
+fn rel(slot: i64) -> Operand {
+  Operand::Rel(Word::Int(slot))
+}
+
+#[derive(Clone, Copy)]
+enum Slot {
+  Scalar(i64),
+  Array(i64),
+}
+
+struct Scope {
+  params: i64,
+  locals: i64,
+  temps: i64,
+  max_temps: i64,
+  names: HashMap<String, Slot>,
+}
+
+pub struct Generator {
+  code: Vec<Word>,
+  labels: Vec<Option<i64>>,
+  functions: HashMap<String, (usize, usize)>,
+  scratch: usize,
+  negated: usize,
+}
+
+impl Generator {
+  // Errors that belong to no statement are reported on the last line.
+  pub fn generate(functions: &[Function], last_line: usize) -> Result<Vec<i64>, Error> {
+    let mut generator = Generator {
+      code: Vec::new(),
+      labels: Vec::new(),
+      functions: HashMap::new(),
+      scratch: 0,
+      negated: 0,
+    };
+    generator.scratch = generator.label();
+    generator.negated = generator.label();
+    for function in functions {
+      let label = generator.label();
+      if generator
+        .functions
+        .insert(function.name.clone(), (label, function.params.len()))
+        .is_some()
+      {
+        return Err(Error::Name(
+          function.line,
+          format!("function {} is defined twice", function.name),
+        ));
+      }
+    }
+    match functions.iter().find(|function| function.name == "main") {
+      Some(main) if main.params.is_empty() => (),
+      Some(main) => {
+        return Err(Error::Name(
+          main.line,
+          "main must not take parameters".into(),
+        ))
+      }
+      None => return Err(Error::Name(last_line, "no main function".into())),
+    }
+    let stack = generator.label();
+    let halt = generator.label();
+    let main = generator.functions["main"].0;
+    generator.emit(RELATIVE, &[Operand::Imm(Word::Label(stack))]);
+    generator.emit(
+      ADD,
+      &[Operand::Imm(Word::Label(halt)), imm(0), rel(RETURN_ADDRESS)],
+    );
+    generator.emit(JUMP_IF_TRUE, &[imm(1), Operand::Imm(Word::Label(main))]);
+    generator.place(halt);
+    generator.emit(HALT, &[]);
+    for function in functions {
+      generator.function(function)?;
+    }
+    generator.place(generator.scratch);
+    generator.code.push(Word::Int(0));
+    generator.place(generator.negated);
+    generator.code.push(Word::Int(0));
+    generator.place(stack);
+    Ok(generator.resolve())
+  }
+
+  fn label(&mut self) -> usize {
+    self.labels.push(None);
+    self.labels.len() - 1
+  }
+  fn place(&mut self, label: usize) {
+    self.labels[label] = Some(self.code.len() as i64);
+  }
+  fn emit(&mut self, code: i64, operands: &[Operand]) {
+    let modes = operands
+      .iter()
+      .enumerate()
+      .map(|(idx, operand)| operand.mode() * 10i64.pow(idx as u32 + 2))
+      .sum::<i64>();
+    self.code.push(Word::Int(code + modes));
+    self
+      .code
+      .extend(operands.iter().map(|operand| operand.word()));
+  }
+  fn jump(&mut self, label: usize) {
+    self.emit(JUMP_IF_TRUE, &[imm(1), Operand::Imm(Word::Label(label))]);
+  }
+  fn copy(&mut self, from: Operand, to: Operand) {
+    self.emit(ADD, &[from, imm(0), to]);
+  }
+  // Every label is placed and every frame slot is filled in by the time the
+  // program is complete, anything else is a bug in the generator.
+  fn resolve(&self) -> Vec<i64> {
+    self
+      .code
+      .iter()
+      .map(|word| match word {
+        Word::Int(value) => *value,
+        Word::Label(label) => {
+          self.labels[*label].unwrap_or_else(|| panic!("label {} was never placed", label))
+        }
+        word => panic!("unresolved frame slot {:?}", word),
+      })
+      .collect()
+  }
+
+  fn function(&mut self, function: &Function) -> Result<(), Error> {
+    let (label, _) = self.functions[&function.name];
+    self.place(label);
+    let start = self.code.len();
+    let mut scope = Scope {
+      params: function.params.len() as i64,
+      locals: 0,
+      temps: 0,
+      max_temps: 0,
+      names: HashMap::new(),
+    };
+    for (idx, param) in function.params.iter().enumerate() {
+      if scope
+        .names
+        .insert(param.clone(), Slot::Scalar(FIRST_PARAM + idx as i64))
+        .is_some()
+      {
+        return Err(Error::Name(
+          function.line,
+          format!("parameter {} of {} is repeated", param, function.name),
+        ));
+      }
+    }
+    let context = Context {
+      function: &function.name,
+    };
+    self.block(&mut scope, &context, &function.body)?;
+    self.copy(imm(0), rel(RETURN_VALUE));
+    self.emit(JUMP_IF_TRUE, &[imm(1), rel(RETURN_ADDRESS)]);
+
+    let temps = FIRST_PARAM + scope.params + scope.locals;
+    let size = temps + scope.max_temps;
+    self.code[start..].iter_mut().for_each(|word| {
+      *word = match *word {
+        Word::Temp(slot) => Word::Int(temps + slot),
+        Word::Frame(offset) => Word::Int(size + offset),
+        Word::Back => Word::Int(-size),
+        word => word,
+      }
+    });
+    Ok(())
+  }
+
+  fn block(&mut self, scope: &mut Scope, context: &Context, body: &Block) -> Result<(), Error> {
+    let names = scope.names.clone();
+    body
+      .iter()
+      .try_for_each(|(line, stmt)| self.statement(scope, context, *line, stmt))?;
+    scope.names = names;
+    Ok(())
+  }
+
+  fn declare(
+    &self,
+    scope: &mut Scope,
+    context: &Context,
+    line: usize,
+    name: &str,
+    slot: Slot,
+  ) -> Result<(), Error> {
+    if scope.names.insert(name.to_owned(), slot).is_some() {
+      return Err(context.error(line, format!("{} is declared twice", name)));
+    }
+    Ok(())
+  }
+
+  fn statement(
+    &mut self,
+    scope: &mut Scope,
+    context: &Context,
+    line: usize,
+    stmt: &Stmt,
+  ) -> Result<(), Error> {
+    match stmt {
+      Stmt::Var(name, value) => {
+        let slot = FIRST_PARAM + scope.params + scope.locals;
+        scope.locals += 1;
+        let value = match value {
+          Some(value) => self.expression(scope, context, value)?,
+          None => imm(0),
+        };
+        self.copy(value, rel(slot));
+        scope.temps = 0;
+        self.declare(scope, context, line, name, Slot::Scalar(slot))
+      }
+      Stmt::Array(name, size) => {
+        let slot = FIRST_PARAM + scope.params + scope.locals;
+        scope.locals += size;
+        (0..*size).for_each(|idx| self.copy(imm(0), rel(slot + idx)));
+        self.declare(scope, context, line, name, Slot::Array(slot))
+      }
+      Stmt::Assign(name, value) => {
+        let slot = match scope.names.get(name) {
+          Some(Slot::Scalar(slot)) => *slot,
+          Some(Slot::Array(_)) => return Err(context.error(line, format!("{} is an array", name))),
+          None => return Err(context.error(line, format!("{} is not declared", name))),
+        };
+        let value = self.expression(scope, context, value)?;
+        self.copy(value, rel(slot));
+        scope.temps = 0;
+        Ok(())
+      }
+      Stmt::Store(name, index, value) => {
+        let base = self.array(scope, context, line, name)?;
+        let index = self.expression(scope, context, index)?;
+        let value = self.expression(scope, context, value)?;
+        let scratch = Operand::Pos(Word::Label(self.scratch));
+        let negated = Operand::Pos(Word::Label(self.negated));
+        self.copy(value, scratch);
+        self.emit(MULTIPLY, &[index, imm(-1), negated]);
+        self.emit(RELATIVE, &[index]);
+        self.copy(scratch, rel(base));
+        self.emit(RELATIVE, &[negated]);
+        scope.temps = 0;
+        Ok(())
+      }
+      Stmt::If(condition, then, otherwise) => {
+        let otherwise_label = self.label();
+        let end = self.label();
+        let condition = self.expression(scope, context, condition)?;
+        scope.temps = 0;
+        self.emit(
+          JUMP_IF_FALSE,
+          &[condition, Operand::Imm(Word::Label(otherwise_label))],
+        );
+        self.block(scope, context, then)?;
+        self.jump(end);
+        self.place(otherwise_label);
+        self.block(scope, context, otherwise)?;
+        self.place(end);
+        Ok(())
+      }
+      Stmt::While(condition, body) => {
+        let top = self.label();
+        let end = self.label();
+        self.place(top);
+        let condition = self.expression(scope, context, condition)?;
+        scope.temps = 0;
+        self.emit(JUMP_IF_FALSE, &[condition, Operand::Imm(Word::Label(end))]);
+        self.block(scope, context, body)?;
+        self.jump(top);
+        self.place(end);
+        Ok(())
+      }
+      Stmt::Return(value) => {
+        let value = match value {
+          Some(value) => self.expression(scope, context, value)?,
+          None => imm(0),
+        };
+        scope.temps = 0;
+        self.copy(value, rel(RETURN_VALUE));
+        self.emit(JUMP_IF_TRUE, &[imm(1), rel(RETURN_ADDRESS)]);
+        Ok(())
+      }
+      Stmt::Output(value) => {
+        let value = self.expression(scope, context, value)?;
+        scope.temps = 0;
+        self.emit(OUTPUT, &[value]);
+        Ok(())
+      }
+      Stmt::Expr(value) => {
+        self.expression(scope, context, value)?;
+        scope.temps = 0;
+        Ok(())
+      }
+    }
+  }
+
+  fn array(&self, scope: &Scope, context: &Context, line: usize, name: &str) -> Result<i64, Error> {
+    match scope.names.get(name) {
+      Some(Slot::Array(slot)) => Ok(*slot),
+      Some(Slot::Scalar(_)) => Err(context.error(line, format!("{} is not an array", name))),
+      None => Err(context.error(line, format!("{} is not declared", name))),
+    }
+  }
+
+  fn temp(&self, scope: &mut Scope) -> Operand {
+    let slot = scope.temps;
+    scope.temps += 1;
+    scope.max_temps = std::cmp::max(scope.max_temps, scope.temps);
+    Operand::Rel(Word::Temp(slot))
+  }
+
+  fn expression(
+    &mut self,
+    scope: &mut Scope,
+    context: &Context,
+    expr: &Expr,
+  ) -> Result<Operand, Error> {
+    let mark = scope.temps;
+    match expr {
+      Expr::Number(value) => Ok(imm(*value)),
+      Expr::Var(line, name) => match scope.names.get(name) {
+        Some(Slot::Scalar(slot)) => Ok(rel(*slot)),
+        Some(Slot::Array(_)) => Err(context.error(*line, format!("{} is an array", name))),
+        None => Err(context.error(*line, format!("{} is not declared", name))),
+      },
+      Expr::Index(line, name, index) => {
+        let base = self.array(scope, context, *line, name)?;
+        let index = self.expression(scope, context, index)?;
+        let scratch = Operand::Pos(Word::Label(self.scratch));
+        let negated = Operand::Pos(Word::Label(self.negated));
+        self.emit(MULTIPLY, &[index, imm(-1), negated]);
+        self.emit(RELATIVE, &[index]);
+        self.copy(rel(base), scratch);
+        self.emit(RELATIVE, &[negated]);
+        scope.temps = mark;
+        let result = self.temp(scope);
+        self.copy(scratch, result);
+        Ok(result)
+      }
+      Expr::Input => {
+        let result = self.temp(scope);
+        self.emit(INPUT, &[result]);
+        Ok(result)
+      }
+      Expr::Neg(value) => {
+        let value = self.expression(scope, context, value)?;
+        scope.temps = mark;
+        let result = self.temp(scope);
+        self.emit(MULTIPLY, &[value, imm(-1), result]);
+        Ok(result)
+      }
+      Expr::Not(value) => {
+        let value = self.expression(scope, context, value)?;
+        scope.temps = mark;
+        let result = self.temp(scope);
+        self.emit(EQUALS, &[value, imm(0), result]);
+        Ok(result)
+      }
+      Expr::Binary(op, left, right) => {
+        let left = self.expression(scope, context, left)?;
+        let right = self.expression(scope, context, right)?;
+        scope.temps = mark;
+        let result = self.temp(scope);
+        self.binary(*op, left, right, result);
+        Ok(result)
+      }
+      Expr::Call(line, name, args) => {
+        let (label, arity) = match self.functions.get(name) {
+          Some(function) => *function,
+          None => return Err(context.error(*line, format!("function {} is not defined", name))),
+        };
+        if arity != args.len() {
+          return Err(context.error(
+            *line,
+            format!("{} takes {} arguments, not {}", name, arity, args.len()),
+          ));
+        }
+        let mut values = Vec::new();
+        for arg in args {
+          let value = self.expression(scope, context, arg)?;
+          let value = match value {
+            Operand::Rel(Word::Temp(_)) | Operand::Imm(_) => value,
+            _ => {
+              let temp = self.temp(scope);
+              self.copy(value, temp);
+              temp
+            }
+          };
+          values.push(value);
+        }
+        values.iter().enumerate().for_each(|(idx, value)| {
+          self.copy(*value, Operand::Rel(Word::Frame(FIRST_PARAM + idx as i64)))
+        });
+        let back = self.label();
+        self.copy(
+          Operand::Imm(Word::Label(back)),
+          Operand::Rel(Word::Frame(RETURN_ADDRESS)),
+        );
+        self.emit(RELATIVE, &[Operand::Imm(Word::Frame(0))]);
+        self.jump(label);
+        self.place(back);
+        self.emit(RELATIVE, &[Operand::Imm(Word::Back)]);
+        scope.temps = mark;
+        let result = self.temp(scope);
+        self.copy(Operand::Rel(Word::Frame(RETURN_VALUE)), result);
+        Ok(result)
+      }
+    }
+  }
+
+  fn binary(&mut self, op: BinOp, left: Operand, right: Operand, result: Operand) {
+    match op {
+      BinOp::Add => self.emit(ADD, &[left, right, result]),
+      BinOp::Sub => {
+        let negated = Operand::Pos(Word::Label(self.negated));
+        self.emit(MULTIPLY, &[right, imm(-1), negated]);
+        self.emit(ADD, &[left, negated, result]);
+      }
+      BinOp::Mul => self.emit(MULTIPLY, &[left, right, result]),
+      BinOp::Less => self.emit(LESS_THAN, &[left, right, result]),
+      BinOp::Greater => self.emit(LESS_THAN, &[right, left, result]),
+      BinOp::LessEqual => {
+        self.emit(LESS_THAN, &[right, left, result]);
+        self.emit(EQUALS, &[result, imm(0), result]);
+      }
+      BinOp::GreaterEqual => {
+        self.emit(LESS_THAN, &[left, right, result]);
+        self.emit(EQUALS, &[result, imm(0), result]);
+      }
+      BinOp::Equal => self.emit(EQUALS, &[left, right, result]),
+      BinOp::NotEqual => {
+        self.emit(EQUALS, &[left, right, result]);
+        self.emit(EQUALS, &[result, imm(0), result]);
+      }
+      BinOp::And | BinOp::Or => {
+        let negated = Operand::Pos(Word::Label(self.negated));
+        self.emit(EQUALS, &[left, imm(0), negated]);
+        self.emit(EQUALS, &[right, imm(0), result]);
+        if op == BinOp::And {
+          self.emit(ADD, &[negated, result, result]);
+        } else {
+          self.emit(MULTIPLY, &[negated, result, result]);
+        }
+        self.emit(EQUALS, &[result, imm(0), result]);
+      }
+    }
+  }
+}
+
+struct Context<'a> {
+  function: &'a str,
+}
+
+impl<'a> Context<'a> {
+  fn error(&self, line: usize, message: String) -> Error {
+    Error::Name(line, format!("in {}: {}", self.function, message))
+  }
+}
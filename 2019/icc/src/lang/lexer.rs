@@ -0,0 +1,154 @@
+use super::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+  Number(i64),
+  Name(String),
+  Fn,
+  Var,
+  If,
+  Else,
+  While,
+  Return,
+  Input,
+  Output,
+  Open,
+  Close,
+  OpenBrace,
+  CloseBrace,
+  OpenBracket,
+  CloseBracket,
+  Comma,
+  Semicolon,
+  Assign,
+  Plus,
+  Minus,
+  Star,
+  Less,
+  LessEqual,
+  Greater,
+  GreaterEqual,
+  Equal,
+  NotEqual,
+  Not,
+  And,
+  Or,
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, Error> {
+  let mut tokens = Vec::new();
+  for (idx, line) in source.lines().enumerate() {
+    let line_no = idx + 1;
+    let line = match line.find("//") {
+      Some(pos) => &line[..pos],
+      None => line,
+    };
+    let chars: Vec<char> = line.chars().collect();
+    let mut pos = 0;
+    while pos < chars.len() {
+      let c = chars[pos];
+      let next = chars.get(pos + 1).cloned();
+      if c.is_whitespace() {
+        pos += 1;
+        continue;
+      }
+      if c.is_ascii_digit() {
+        let start = pos;
+        while pos < chars.len() && chars[pos].is_ascii_digit() {
+          pos += 1;
+        }
+        let text: String = chars[start..pos].iter().collect();
+        let value = text
+          .parse()
+          .map_err(|_| Error::Syntax(line_no, format!("number {} is too large", text)))?;
+        tokens.push((line_no, Token::Number(value)));
+        continue;
+      }
+      if c.is_alphabetic() || c == '_' {
+        let start = pos;
+        while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '_') {
+          pos += 1;
+        }
+        let word: String = chars[start..pos].iter().collect();
+        let token = match word.as_str() {
+          "fn" => Token::Fn,
+          "var" => Token::Var,
+          "if" => Token::If,
+          "else" => Token::Else,
+          "while" => Token::While,
+          "return" => Token::Return,
+          "input" => Token::Input,
+          "output" => Token::Output,
+          _ => Token::Name(word),
+        };
+        tokens.push((line_no, token));
+        continue;
+      }
+      let (token, width) = match (c, next) {
+        ('<', Some('=')) => (Token::LessEqual, 2),
+        ('>', Some('=')) => (Token::GreaterEqual, 2),
+        ('=', Some('=')) => (Token::Equal, 2),
+        ('!', Some('=')) => (Token::NotEqual, 2),
+        ('&', Some('&')) => (Token::And, 2),
+        ('|', Some('|')) => (Token::Or, 2),
+        ('(', _) => (Token::Open, 1),
+        (')', _) => (Token::Close, 1),
+        ('{', _) => (Token::OpenBrace, 1),
+        ('}', _) => (Token::CloseBrace, 1),
+        ('[', _) => (Token::OpenBracket, 1),
+        (']', _) => (Token::CloseBracket, 1),
+        (',', _) => (Token::Comma, 1),
+        (';', _) => (Token::Semicolon, 1),
+        ('=', _) => (Token::Assign, 1),
+        ('+', _) => (Token::Plus, 1),
+        ('-', _) => (Token::Minus, 1),
+        ('*', _) => (Token::Star, 1),
+        ('<', _) => (Token::Less, 1),
+        ('>', _) => (Token::Greater, 1),
+        ('!', _) => (Token::Not, 1),
+        (c, _) => {
+          return Err(Error::Syntax(
+            line_no,
+            format!("unexpected character '{}'", c),
+          ))
+        }
+      };
+      tokens.push((line_no, token));
+      pos += width;
+    }
+  }
+  Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn tokens_1() {
+    let tokens: Vec<Token> = tokenize("var x = 12; // twelve\nx <= 3")
+      .unwrap()
+      .into_iter()
+      .map(|(_, t)| t)
+      .collect();
+    assert_eq!(
+      tokens,
+      vec![
+        Token::Var,
+        Token::Name("x".into()),
+        Token::Assign,
+        Token::Number(12),
+        Token::Semicolon,
+        Token::Name("x".into()),
+        Token::LessEqual,
+        Token::Number(3),
+      ]
+    );
+  }
+  #[test]
+  fn bad_char() {
+    assert_eq!(
+      tokenize("var x;\nx = 7 / 2;"),
+      Err(Error::Syntax(2, "unexpected character '/'".into()))
+    );
+  }
+}
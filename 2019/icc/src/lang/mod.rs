@@ -0,0 +1,233 @@
+mod codegen;
+mod lexer;
+mod parser;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+  Syntax(usize, String),
+  Name(usize, String),
+}
+impl std::fmt::Display for Error {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Error::Syntax(line, message) => write!(fmt, "Syntax error on line {}: {}.", line, message),
+      Error::Name(line, message) => write!(fmt, "Name error on line {}: {}.", line, message),
+    }
+  }
+}
+
+pub fn compile(source: &str) -> Result<Vec<i64>, Error> {
+  let tokens = lexer::tokenize(source)?;
+  let functions = parser::Parser::new(tokens).program()?;
+  codegen::Generator::generate(&functions, source.lines().count().max(1))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::{run_to_end, Computer};
+  use super::*;
+
+  fn run(source: &str, input: &[i64]) -> Vec<i64> {
+    let program = compile(source).expect("should compile");
+    let mut computer = Computer::load(0, &program);
+    run_to_end(&mut computer, input).expect("should run")
+  }
+
+  #[test]
+  fn echo() {
+    let source = "fn main() { output(input()); }";
+    assert_eq!(run(source, &[42]), vec![42]);
+  }
+  #[test]
+  fn arithmetic() {
+    let source = "
+      fn main() {
+        var a = input();
+        var b = input();
+        output(a + b);
+        output(a - b);
+        output(a * b);
+        output(-a);
+        output(a - b * 2 + 1);
+      }";
+    assert_eq!(run(source, &[7, 3]), vec![10, 4, 21, -7, 2]);
+  }
+  #[test]
+  fn comparisons() {
+    let source = "
+      fn main() {
+        var a = input();
+        var b = input();
+        output(a < b);
+        output(a <= b);
+        output(a > b);
+        output(a >= b);
+        output(a == b);
+        output(a != b);
+        output(a < b && b < 10);
+        output(a > b || b == 5);
+        output(!a);
+      }";
+    assert_eq!(run(source, &[3, 5]), vec![1, 1, 0, 0, 0, 1, 1, 1, 0]);
+    assert_eq!(run(source, &[5, 5]), vec![0, 1, 0, 1, 1, 0, 0, 1, 0]);
+  }
+  #[test]
+  fn sum_loop() {
+    let source = "
+      fn main() {
+        var n = input();
+        var sum = 0;
+        while (n > 0) {
+          sum = sum + n;
+          n = n - 1;
+        }
+        output(sum);
+      }";
+    assert_eq!(run(source, &[100]), vec![5050]);
+  }
+  #[test]
+  fn branches() {
+    let source = "
+      fn sign(x) {
+        if (x < 0) {
+          return -1;
+        } else if (x == 0) {
+          return 0;
+        }
+        return 1;
+      }
+      fn main() {
+        output(sign(input()));
+        output(sign(input()));
+        output(sign(input()));
+      }";
+    assert_eq!(run(source, &[-12, 0, 9]), vec![-1, 0, 1]);
+  }
+  #[test]
+  fn recursion() {
+    let source = "
+      fn fact(n) {
+        if (n < 2) {
+          return 1;
+        }
+        return n * fact(n - 1);
+      }
+      fn fib(n) {
+        if (n < 2) {
+          return n;
+        }
+        return fib(n - 1) + fib(n - 2);
+      }
+      fn main() {
+        output(fact(10));
+        output(fib(15));
+      }";
+    assert_eq!(run(source, &[]), vec![3_628_800, 610]);
+  }
+  #[test]
+  fn nested_calls() {
+    let source = "
+      fn add(a, b) { return a + b; }
+      fn mul(a, b) { return a * b; }
+      fn main() {
+        var x = 3;
+        output(add(mul(x, 4), add(mul(2, 5), x)));
+      }";
+    assert_eq!(run(source, &[]), vec![25]);
+  }
+  #[test]
+  fn arrays() {
+    let source = "
+      fn main() {
+        var n = input();
+        var data[16];
+        var i = 0;
+        while (i < n) {
+          data[i] = input();
+          i = i + 1;
+        }
+        var swapped = 1;
+        while (swapped) {
+          swapped = 0;
+          i = 1;
+          while (i < n) {
+            if (data[i - 1] > data[i]) {
+              var t = data[i];
+              data[i] = data[i - 1];
+              data[i - 1] = t;
+              swapped = 1;
+            }
+            i = i + 1;
+          }
+        }
+        i = 0;
+        while (i < n) {
+          output(data[i]);
+          i = i + 1;
+        }
+      }";
+    assert_eq!(
+      run(source, &[6, 5, -3, 12, 0, 7, 1]),
+      vec![-3, 0, 1, 5, 7, 12]
+    );
+  }
+  #[test]
+  fn arrays_per_frame() {
+    let source = "
+      fn fill(n) {
+        var squares[10];
+        var i = 0;
+        while (i < 10) {
+          squares[i] = i * n;
+          i = i + 1;
+        }
+        if (n > 1) {
+          fill(n - 1);
+        }
+        return squares[9];
+      }
+      fn main() { output(fill(4)); }";
+    assert_eq!(run(source, &[]), vec![36]);
+  }
+  #[test]
+  fn syntax_error() {
+    assert_eq!(
+      compile("fn main() {\n  output(1 +);\n}"),
+      Err(Error::Syntax(
+        2,
+        "expected an expression, found Close".into()
+      ))
+    );
+  }
+  #[test]
+  fn name_errors() {
+    assert_eq!(
+      compile("fn main() {\n  output(x);\n}"),
+      Err(Error::Name(2, "in main: x is not declared".into()))
+    );
+    assert_eq!(
+      compile("fn main() {\n  var x;\n  output(1);\n  var x = 2;\n}"),
+      Err(Error::Name(4, "in main: x is declared twice".into()))
+    );
+    assert_eq!(
+      compile("fn main() {\n  var a[2];\n  output(1 +\n    a);\n}"),
+      Err(Error::Name(4, "in main: a is an array".into()))
+    );
+    assert_eq!(
+      compile("fn main() {\n  if (1) {\n    y = 1;\n  }\n}"),
+      Err(Error::Name(3, "in main: y is not declared".into()))
+    );
+    assert_eq!(
+      compile("fn f(a) { return a; }\nfn main() { output(f()); }"),
+      Err(Error::Name(2, "in main: f takes 1 arguments, not 0".into()))
+    );
+    assert_eq!(
+      compile("fn f() { return 1; }\n\nfn g() { return 2; }"),
+      Err(Error::Name(3, "no main function".into()))
+    );
+    assert_eq!(
+      compile("fn f() { return 1; }\nfn main(a) { output(a); }"),
+      Err(Error::Name(2, "main must not take parameters".into()))
+    );
+  }
+}
@@ -0,0 +1,388 @@
+use super::lexer::Token;
+use super::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinOp {
+  Add,
+  Sub,
+  Mul,
+  Less,
+  LessEqual,
+  Greater,
+  GreaterEqual,
+  Equal,
+  NotEqual,
+  And,
+  Or,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+  Number(i64),
+  Var(usize, String),
+  Index(usize, String, Box<Expr>),
+  Call(usize, String, Vec<Expr>),
+  Input,
+  Neg(Box<Expr>),
+  Not(Box<Expr>),
+  Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+  Var(String, Option<Expr>),
+  Array(String, i64),
+  Assign(String, Expr),
+  Store(String, Expr, Expr),
+  If(Expr, Block, Block),
+  While(Expr, Block),
+  Return(Option<Expr>),
+  Output(Expr),
+  Expr(Expr),
+}
+
+// Every statement with the line it starts on.
+pub type Block = Vec<(usize, Stmt)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+  pub line: usize,
+  pub name: String,
+  pub params: Vec<String>,
+  pub body: Block,
+}
+
+pub struct Parser {
+  tokens: Vec<(usize, Token)>,
+  pos: usize,
+}
+
+impl Parser {
+  pub fn new(tokens: Vec<(usize, Token)>) -> Parser {
+    Parser { tokens, pos: 0 }
+  }
+
+  pub fn program(&mut self) -> Result<Vec<Function>, Error> {
+    let mut functions = Vec::new();
+    while self.peek().is_some() {
+      functions.push(self.function()?);
+    }
+    Ok(functions)
+  }
+
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos).map(|(_, t)| t)
+  }
+  fn line(&self) -> usize {
+    match self.tokens.get(self.pos) {
+      Some((line, _)) => *line,
+      None => self.tokens.last().map(|(line, _)| *line).unwrap_or(1),
+    }
+  }
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.pos).map(|(_, t)| t.clone());
+    self.pos += 1;
+    token
+  }
+  fn error<T>(&self, expected: &str) -> Result<T, Error> {
+    let found = match self.peek() {
+      Some(token) => format!("{:?}", token),
+      None => "end of input".to_owned(),
+    };
+    Err(Error::Syntax(
+      self.line(),
+      format!("expected {}, found {}", expected, found),
+    ))
+  }
+  fn accept(&mut self, token: &Token) -> bool {
+    if self.peek() == Some(token) {
+      self.pos += 1;
+      return true;
+    }
+    false
+  }
+  fn expect(&mut self, token: Token, expected: &str) -> Result<(), Error> {
+    if self.accept(&token) {
+      return Ok(());
+    }
+    self.error(expected)
+  }
+  fn name(&mut self) -> Result<String, Error> {
+    match self.peek() {
+      Some(Token::Name(name)) => {
+        let name = name.clone();
+        self.pos += 1;
+        Ok(name)
+      }
+      _ => self.error("a name"),
+    }
+  }
+
+  fn function(&mut self) -> Result<Function, Error> {
+    let line = self.line();
+    self.expect(Token::Fn, "'fn'")?;
+    let name = self.name()?;
+    self.expect(Token::Open, "'('")?;
+    let mut params = Vec::new();
+    if !self.accept(&Token::Close) {
+      loop {
+        params.push(self.name()?);
+        if self.accept(&Token::Close) {
+          break;
+        }
+        self.expect(Token::Comma, "',' or ')'")?;
+      }
+    }
+    let body = self.block()?;
+    Ok(Function {
+      line,
+      name,
+      params,
+      body,
+    })
+  }
+
+  fn block(&mut self) -> Result<Block, Error> {
+    self.expect(Token::OpenBrace, "'{'")?;
+    let mut body = Vec::new();
+    while !self.accept(&Token::CloseBrace) {
+      if self.peek().is_none() {
+        return self.error("'}'");
+      }
+      body.push((self.line(), self.statement()?));
+    }
+    Ok(body)
+  }
+
+  fn statement(&mut self) -> Result<Stmt, Error> {
+    match self.peek() {
+      Some(Token::Var) => {
+        self.pos += 1;
+        let name = self.name()?;
+        let stmt = if self.accept(&Token::OpenBracket) {
+          let size = match self.next() {
+            Some(Token::Number(size)) if size > 0 => size,
+            _ => {
+              self.pos -= 1;
+              return self.error("an array size");
+            }
+          };
+          self.expect(Token::CloseBracket, "']'")?;
+          Stmt::Array(name, size)
+        } else if self.accept(&Token::Assign) {
+          Stmt::Var(name, Some(self.expression()?))
+        } else {
+          Stmt::Var(name, None)
+        };
+        self.expect(Token::Semicolon, "';'")?;
+        Ok(stmt)
+      }
+      Some(Token::If) => {
+        self.pos += 1;
+        let condition = self.condition()?;
+        let then = self.block()?;
+        let otherwise = if self.accept(&Token::Else) {
+          if self.peek() == Some(&Token::If) {
+            vec![(self.line(), self.statement()?)]
+          } else {
+            self.block()?
+          }
+        } else {
+          Vec::new()
+        };
+        Ok(Stmt::If(condition, then, otherwise))
+      }
+      Some(Token::While) => {
+        self.pos += 1;
+        let condition = self.condition()?;
+        Ok(Stmt::While(condition, self.block()?))
+      }
+      Some(Token::Return) => {
+        self.pos += 1;
+        let value = if self.peek() == Some(&Token::Semicolon) {
+          None
+        } else {
+          Some(self.expression()?)
+        };
+        self.expect(Token::Semicolon, "';'")?;
+        Ok(Stmt::Return(value))
+      }
+      Some(Token::Output) => {
+        self.pos += 1;
+        let value = self.condition()?;
+        self.expect(Token::Semicolon, "';'")?;
+        Ok(Stmt::Output(value))
+      }
+      Some(Token::Name(_)) => {
+        let after = self.tokens.get(self.pos + 1).map(|(_, t)| t.clone());
+        let stmt = match (after, self.name()?) {
+          (Some(Token::Assign), name) => {
+            self.pos += 1;
+            Stmt::Assign(name, self.expression()?)
+          }
+          (Some(Token::OpenBracket), name) => {
+            self.pos += 1;
+            let index = self.expression()?;
+            self.expect(Token::CloseBracket, "']'")?;
+            if self.accept(&Token::Assign) {
+              Stmt::Store(name, index, self.expression()?)
+            } else {
+              return self.error("'='");
+            }
+          }
+          _ => {
+            self.pos -= 1;
+            Stmt::Expr(self.expression()?)
+          }
+        };
+        self.expect(Token::Semicolon, "';'")?;
+        Ok(stmt)
+      }
+      _ => self.error("a statement"),
+    }
+  }
+
+  fn condition(&mut self) -> Result<Expr, Error> {
+    self.expect(Token::Open, "'('")?;
+    let expr = self.expression()?;
+    self.expect(Token::Close, "')'")?;
+    Ok(expr)
+  }
+
+  fn expression(&mut self) -> Result<Expr, Error> {
+    self.binary(0)
+  }
+
+  fn binary(&mut self, level: usize) -> Result<Expr, Error> {
+    const LEVELS: [&[(Token, BinOp)]; 5] = [
+      &[(Token::Or, BinOp::Or)],
+      &[(Token::And, BinOp::And)],
+      &[
+        (Token::Equal, BinOp::Equal),
+        (Token::NotEqual, BinOp::NotEqual),
+      ],
+      &[
+        (Token::Less, BinOp::Less),
+        (Token::LessEqual, BinOp::LessEqual),
+        (Token::Greater, BinOp::Greater),
+        (Token::GreaterEqual, BinOp::GreaterEqual),
+      ],
+      &[(Token::Plus, BinOp::Add), (Token::Minus, BinOp::Sub)],
+    ];
+    if level == LEVELS.len() {
+      return self.product();
+    }
+    let mut left = self.binary(level + 1)?;
+    'outer: loop {
+      for (token, op) in LEVELS[level].iter() {
+        if self.accept(token) {
+          let right = self.binary(level + 1)?;
+          left = Expr::Binary(*op, Box::new(left), Box::new(right));
+          continue 'outer;
+        }
+      }
+      return Ok(left);
+    }
+  }
+
+  fn product(&mut self) -> Result<Expr, Error> {
+    let mut left = self.unary()?;
+    while self.accept(&Token::Star) {
+      let right = self.unary()?;
+      left = Expr::Binary(BinOp::Mul, Box::new(left), Box::new(right));
+    }
+    Ok(left)
+  }
+
+  fn unary(&mut self) -> Result<Expr, Error> {
+    if self.accept(&Token::Minus) {
+      return Ok(Expr::Neg(Box::new(self.unary()?)));
+    }
+    if self.accept(&Token::Not) {
+      return Ok(Expr::Not(Box::new(self.unary()?)));
+    }
+    self.primary()
+  }
+
+  fn primary(&mut self) -> Result<Expr, Error> {
+    let line = self.line();
+    match self.peek().cloned() {
+      Some(Token::Number(value)) => {
+        self.pos += 1;
+        Ok(Expr::Number(value))
+      }
+      Some(Token::Input) => {
+        self.pos += 1;
+        self.expect(Token::Open, "'('")?;
+        self.expect(Token::Close, "')'")?;
+        Ok(Expr::Input)
+      }
+      Some(Token::Open) => self.condition(),
+      Some(Token::Name(name)) => {
+        self.pos += 1;
+        if self.accept(&Token::OpenBracket) {
+          let index = self.expression()?;
+          self.expect(Token::CloseBracket, "']'")?;
+          Ok(Expr::Index(line, name, Box::new(index)))
+        } else if self.accept(&Token::Open) {
+          let mut args = Vec::new();
+          if !self.accept(&Token::Close) {
+            loop {
+              args.push(self.expression()?);
+              if self.accept(&Token::Close) {
+                break;
+              }
+              self.expect(Token::Comma, "',' or ')'")?;
+            }
+          }
+          Ok(Expr::Call(line, name, args))
+        } else {
+          Ok(Expr::Var(line, name))
+        }
+      }
+      _ => self.error("an expression"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::lexer::tokenize;
+  use super::*;
+
+  fn parse(source: &str) -> Result<Vec<Function>, Error> {
+    Parser::new(tokenize(source)?).program()
+  }
+
+  #[test]
+  fn precedence() {
+    let program = parse("fn main() { return 1 + 2 * 3 < 4 || 0; }").unwrap();
+    let num = |n| Box::new(Expr::Number(n));
+    assert_eq!(
+      program[0].body,
+      vec![(
+        1,
+        Stmt::Return(Some(Expr::Binary(
+          BinOp::Or,
+          Box::new(Expr::Binary(
+            BinOp::Less,
+            Box::new(Expr::Binary(
+              BinOp::Add,
+              num(1),
+              Box::new(Expr::Binary(BinOp::Mul, num(2), num(3)))
+            )),
+            num(4)
+          )),
+          num(0)
+        )))
+      )]
+    );
+  }
+  #[test]
+  fn missing_semicolon() {
+    assert_eq!(
+      parse("fn main() {\n  var x = 1\n}"),
+      Err(Error::Syntax(3, "expected ';', found CloseBrace".into()))
+    );
+  }
+}
@@ -1,28 +1,46 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 pub mod adama;
-mod error;
+pub mod asm;
+pub mod decompile;
+pub mod dump;
+pub mod error;
+pub mod event;
+mod flow;
+pub mod gdb;
+mod history;
 pub mod input;
 mod instruction;
+pub mod lang;
+pub mod loader;
 mod memory;
 mod opcode;
+pub mod optimize;
 pub mod state;
+pub mod symbolic;
+#[cfg(feature = "async")]
+pub mod task;
+pub mod translate;
+#[cfg(feature = "tui")]
+pub mod tui;
+use dump::Snapshot;
 use error::*;
+use event::Event;
+use history::*;
 use instruction::*;
 use memory::*;
-use opcode::*;
 use state::*;
+use tracing::{debug, trace, warn};
 
 pub fn run_to_end(computer: &mut Computer, input: &[i64]) -> Result<Vec<i64>, String> {
   let mut input: Vec<i64> = input.iter().cloned().rev().collect();
   let mut output = Vec::new();
   loop {
-    match computer.run() {
-      State::Crashed => return Err(computer.error()),
-      State::Halted => break,
-      State::Ready => (),
-      State::Running => (),
-      State::Output(_id, value) => output.push(value),
-      State::Input => {
+    match computer.resume() {
+      Event::Faulted { .. } => return Err(computer.error()),
+      Event::Halted { .. } => break,
+      Event::Breakpoint { .. } | Event::BudgetExhausted { .. } => (),
+      Event::Output { value, .. } => output.push(value),
+      Event::Input { .. } => {
         if !computer.input_value(input.pop().expect("Asked for more input than provided.")) {
           panic!("Asked for but refused input")
         }
@@ -41,6 +59,11 @@ pub struct Computer {
   state: State,
   pc: i64,
   relative_base: i64,
+  image: i64,
+  breakpoints: BTreeSet<i64>,
+  decoded: HashMap<i64, (Instruction, i64)>,
+  pure: Vec<(i64, i64)>,
+  history: History,
 }
 
 impl Computer {
@@ -58,14 +81,59 @@ impl Computer {
       memory: value_pairs,
       pc: 0,
       relative_base: 0,
+      image: code.len() as i64,
+      breakpoints: BTreeSet::new(),
+      decoded: HashMap::new(),
+      pure: Vec::new(),
+      history: History::new(0),
+    }
+  }
+  pub fn load_optimized(id: usize, optimized: &optimize::Optimized) -> Computer {
+    let mut computer = Computer::load(id, &optimized.program);
+    computer.decoded = flow::blocks(&optimized.program)
+      .into_iter()
+      .flat_map(|block| block.body)
+      .filter(|(at, _)| optimized.is_pure(*at))
+      .map(|(at, instruction)| {
+        let length = instruction.length();
+        (at, (instruction, length))
+      })
+      .collect();
+    computer.pure = optimized.pure.clone();
+    computer
+  }
+  pub fn restore(id: usize, snapshot: &Snapshot) -> Computer {
+    let (input, state) = match snapshot.input() {
+      Some(dest) => (Some(Value::Direct(dest)), State::Input),
+      None => (None, State::Ready),
+    };
+    Computer {
+      id,
+      error: None,
+      state,
+      input,
+      memory: snapshot.cells().collect(),
+      pc: snapshot.pc(),
+      relative_base: snapshot.relative_base(),
+      image: snapshot.image(),
+      breakpoints: BTreeSet::new(),
+      decoded: HashMap::new(),
+      pure: Vec::new(),
+      history: History::new(0),
     }
   }
   pub fn step(&mut self) -> State {
+    self.checkpoint();
+    let pc = self.pc;
     let mode_op = self.get_mode_op();
     match mode_op {
-      Ok(instruction) => self.execute_instruction(instruction),
+      Ok(instruction) => {
+        trace!(machine = self.id, pc, instruction = ?instruction, "step");
+        self.execute_instruction(instruction)
+      }
       Err(err) => {
         self.error = Some(err);
+        self.state = State::Crashed;
         State::Crashed
       }
     }
@@ -84,8 +152,27 @@ impl Computer {
     }
   }
 
+  pub fn resume(&mut self) -> Event {
+    let event = self.advance(None);
+    self.report(&event);
+    event
+  }
+  pub fn resume_for(&mut self, budget: usize) -> Event {
+    let event = self.advance(Some(budget));
+    self.report(&event);
+    event
+  }
+  pub fn add_breakpoint(&mut self, pc: i64) {
+    self.breakpoints.insert(pc);
+  }
+  pub fn remove_breakpoint(&mut self, pc: i64) -> bool {
+    self.breakpoints.remove(&pc)
+  }
+
   pub fn input_value(&mut self, value: i64) -> bool {
     if let Some(dest) = self.input.clone() {
+      debug!(machine = self.id, pc = self.pc - 2, value, "input");
+      self.checkpoint();
       match self.write_value(dest, value) {
         Ok(state) => self.state = state,
         Err(error) => {
@@ -112,13 +199,142 @@ impl Computer {
     self.memory.get(&addr).cloned()
   }
   pub fn edit_memory(&mut self, addr: i64, value: i64) {
-    self.memory.insert(addr, value);
+    self.checkpoint();
+    self.set(addr, value);
+  }
+
+  pub fn pc(&self) -> i64 {
+    self.pc
+  }
+  pub fn relative_base(&self) -> i64 {
+    self.relative_base
+  }
+  pub fn state(&self) -> State {
+    self.state.clone()
+  }
+  pub fn snapshot(&self) -> Snapshot {
+    Snapshot::new(
+      self.pc,
+      self.relative_base,
+      self.image,
+      self.memory.iter().map(|(addr, value)| (*addr, *value)),
+    )
+    .with_input(self.input.clone().and_then(|dest| self.destination(dest).ok()))
+  }
+
+  pub fn keep_history(&mut self, depth: usize) {
+    self.history = History::new(depth);
+  }
+  pub fn history_len(&self) -> usize {
+    self.history.len()
+  }
+  pub fn step_back(&mut self) -> bool {
+    match self.history.pop() {
+      Some(entry) => {
+        trace!(
+          machine = self.id,
+          from = self.pc,
+          to = entry.pc,
+          "step back"
+        );
+        entry.writes.iter().rev().for_each(|(addr, old)| match old {
+          Some(value) => {
+            self.memory.insert(*addr, *value);
+          }
+          None => {
+            self.memory.remove(addr);
+          }
+        });
+        self.pc = entry.pc;
+        self.relative_base = entry.relative_base;
+        self.input = entry.input;
+        self.state = entry.state;
+        self.error = entry.error;
+        true
+      }
+      None => false,
+    }
   }
 }
 
 impl Computer {
+  fn checkpoint(&mut self) {
+    if self.history.depth() == 0 {
+      return;
+    }
+    self.history.push(Entry {
+      pc: self.pc,
+      relative_base: self.relative_base,
+      input: self.input.clone(),
+      state: self.state.clone(),
+      error: self.error.clone(),
+      writes: Vec::new(),
+    });
+  }
+  fn report(&self, event: &Event) {
+    let machine = self.id;
+    match event {
+      Event::Output { pc, value } => debug!(machine, pc, value, "output"),
+      Event::Input { pc, destination } => debug!(machine, pc, destination, "input requested"),
+      Event::Halted { pc } => debug!(machine, pc, "halted"),
+      Event::Faulted { pc, error } => warn!(machine, pc, %error, "faulted"),
+      Event::Breakpoint { pc } => debug!(machine, pc, "breakpoint"),
+      Event::BudgetExhausted { pc } => trace!(machine, pc, "budget exhausted"),
+    }
+  }
+  fn advance(&mut self, mut budget: Option<usize>) -> Event {
+    let mut first = true;
+    loop {
+      if let Some(dest) = self.input.clone() {
+        let pc = self.pc - 2;
+        return match self.destination(dest) {
+          Ok(destination) => Event::Input { pc, destination },
+          Err(error) => Event::Faulted { pc, error },
+        };
+      }
+      let pc = self.pc;
+      if self.state == State::Crashed {
+        if let Some(error) = self.error.clone() {
+          return Event::Faulted { pc, error };
+        }
+      }
+      if !first && self.breakpoints.contains(&pc) {
+        return Event::Breakpoint { pc };
+      }
+      first = false;
+      match budget.as_mut() {
+        Some(0) => return Event::BudgetExhausted { pc },
+        Some(left) => *left -= 1,
+        None => (),
+      }
+      match self.step() {
+        State::Output(_, value) => return Event::Output { pc, value },
+        State::Halted => return Event::Halted { pc },
+        State::Crashed => self.pc = pc,
+        _ => (),
+      }
+    }
+  }
+  fn destination(&self, dest: Value) -> Result<i64, Error> {
+    match dest {
+      Value::Stored(addr) => self.read(addr),
+      Value::Direct(addr) => Ok(addr),
+      Value::Relative(addr) => Ok(addr + self.relative_base),
+    }
+  }
   fn set(&mut self, addr: i64, value: i64) {
-    self.memory.insert(addr, value);
+    if !self.pure.is_empty()
+      && self
+        .pure
+        .iter()
+        .any(|(from, to)| *from <= addr && addr < *to)
+    {
+      debug!(machine = self.id, addr, "write into pure region");
+      self.pure.clear();
+      self.decoded.clear();
+    }
+    let old = self.memory.insert(addr, value);
+    self.history.record_write(addr, old);
   }
   fn read(&self, address: i64) -> Result<i64, Error> {
     if address < 0 {
@@ -136,91 +352,17 @@ impl Computer {
       Value::Relative(base) => self.read(base + self.relative_base),
     }
   }
-  fn get_mode(&mut self, mode: Mode) -> Result<Value, Error> {
-    self.pc += 2;
-    let value = self.read(self.pc - 1)?;
-    match mode {
-      Mode::Direct => Ok(Value::Direct(value)),
-      Mode::Stored => Ok(Value::Stored(value)),
-      Mode::Relative => Ok(Value::Relative(value)),
-    }
-  }
-
-  fn put_mode(&mut self, mode: Mode) -> Result<Value, Error> {
-    self.pc += 2;
-    let value = self.read(self.pc - 1)?;
-    match mode {
-      Mode::Direct => Ok(Value::Direct(value)),
-      Mode::Stored => Ok(Value::Stored(value)),
-      Mode::Relative => Ok(Value::Relative(value)),
-    }
-  }
-
-  fn get_value(&self, mode: Mode, offset: i64) -> Result<Value, Error> {
-    let value = self.read(self.pc + offset)?;
-    match mode {
-      Mode::Direct => Ok(Value::Direct(value)),
-      Mode::Stored => Ok(Value::Stored(value)),
-      Mode::Relative => Ok(Value::Relative(value)),
-    }
-  }
-  fn get_triplet(
-    &mut self,
-    mode0: Mode,
-    mode1: Mode,
-    mode_d: Mode,
-  ) -> Result<(Value, Value, Value), Error> {
-    self.pc += 4;
-    Ok((
-      self.get_value(mode0, -3)?,
-      self.get_value(mode1, -2)?,
-      self.get_value(mode_d, -1)?,
-    ))
-  }
-  fn get_duplex(&mut self, mode0: Mode, mode1: Mode) -> Result<(Value, Value), Error> {
-    self.pc += 3;
-    Ok((self.get_value(mode0, -2)?, self.get_value(mode1, -1)?))
-  }
-  fn get_extended_op(&mut self, op: i64) -> Result<Instruction, Error> {
-    let (code, mode0, mode1, mode_d) = Instruction::get_op_modes(op)?;
-    match code {
-      OpCode::Add => Ok(Instruction::Add(self.get_triplet(mode0, mode1, mode_d)?)),
-      OpCode::Multiply => Ok(Instruction::Multiply(
-        self.get_triplet(mode0, mode1, mode_d)?,
-      )),
-      OpCode::LessThan => Ok(Instruction::LessThan(
-        self.get_triplet(mode0, mode1, mode_d)?,
-      )),
-      OpCode::Equals => Ok(Instruction::Equals(self.get_triplet(mode0, mode1, mode_d)?)),
-      OpCode::JumpIfTrue => Ok(Instruction::JumpIfTrue(self.get_duplex(mode0, mode1)?)),
-      OpCode::JumpIfFalse => Ok(Instruction::JumpIfFalse(self.get_duplex(mode0, mode1)?)),
-      OpCode::Read => Ok(Instruction::Input(self.put_mode(mode0)?)),
-      OpCode::Write => Ok(Instruction::Output(self.get_mode(mode0)?)),
-      OpCode::ReduceAbsoluteRelative => {
-        Ok(Instruction::ReduceAbsoluteRelative(self.get_mode(mode0)?))
-      }
-      OpCode::Halt => Ok(Instruction::Halt), // Weird address mode for halt?
-    }
-  }
-
   fn get_mode_op(&mut self) -> Result<Instruction, Error> {
-    let op_value = *self.memory.get(&self.pc).ok_or(Error::Address(self.pc))?;
-    match op_value {
-      1 => Ok(Instruction::Add(self.get_triplet(
-        Mode::Stored,
-        Mode::Stored,
-        Mode::Direct,
-      )?)),
-      2 => Ok(Instruction::Multiply(self.get_triplet(
-        Mode::Stored,
-        Mode::Stored,
-        Mode::Direct,
-      )?)),
-      3 => Ok(Instruction::Input(self.put_mode(Mode::Direct)?)),
-      4 => Ok(Instruction::Output(self.get_mode(Mode::Stored)?)),
-      99 => Ok(Instruction::Halt),
-      _ => self.get_extended_op(op_value),
+    let memory = &self.memory;
+    let (instruction, length) = match self.decoded.get(&self.pc) {
+      Some(decoded) => decoded.clone(),
+      None => Instruction::decode(|addr| memory.get(&addr).cloned(), self.pc)?,
+    };
+    if let Instruction::Halt = instruction {
+      return Ok(instruction);
     }
+    self.pc += length;
+    Ok(instruction)
   }
   fn binary_op(
     &mut self,
@@ -233,19 +375,8 @@ impl Computer {
     self.write_value(dest, result)
   }
   fn write_value(&mut self, dest: Value, value: i64) -> Result<State, Error> {
-    match dest {
-      Value::Stored(addr) => {
-        let addr = self.read(addr)?;
-        self.memory.insert(addr, value);
-      }
-      Value::Direct(addr) => {
-        self.memory.insert(addr, value);
-      }
-      Value::Relative(addr) => {
-        let addr = addr + self.relative_base;
-        self.memory.insert(addr, value);
-      }
-    }
+    let addr = self.destination(dest)?;
+    self.set(addr, value);
     Ok(State::Running)
   }
   fn output_value(&mut self, src: Value) -> Result<State, Error> {
@@ -575,4 +706,14 @@ mod tests {
 
     assert_eq!(actual, expected);
   }
+  #[test]
+  fn solve_cent_1() {
+    let comp = Computer::load(
+      ID,
+      &helpers::loader::load_integer_row_list("../day/02/input.csv")[0],
+    );
+    assert_eq!(input::solve_cent(&comp, 6_627_023), Some(1202));
+    assert_eq!(input::solve_cent(&comp, 19_690_720), Some(4019));
+    assert_eq!(input::solve_cent(&comp, 19_690_720 + 100), None);
+  }
 }
@@ -0,0 +1,343 @@
+use super::dump::Snapshot;
+use std::io::Read;
+use std::path::Path;
+
+pub const MAGIC: &[u8; 4] = b"\0icc";
+const HEADER: &str = "snapshot";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+  Syntax(usize, usize, String),
+  Binary(usize, String),
+  Io(String),
+}
+impl std::fmt::Display for Error {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Error::Syntax(line, column, msg) => {
+        write!(fmt, "Syntax error on line {} column {}: {}.", line, column, msg)
+      }
+      Error::Binary(offset, msg) => write!(fmt, "Binary error at byte {}: {}.", offset, msg),
+      Error::Io(msg) => write!(fmt, "Failed to read program: {}.", msg),
+    }
+  }
+}
+
+pub fn parse(bytes: &[u8]) -> Result<Snapshot, Error> {
+  if bytes.starts_with(MAGIC) {
+    return parse_binary(bytes);
+  }
+  match std::str::from_utf8(bytes) {
+    Ok(source) => from_str(source),
+    Err(err) => {
+      let (line, column) = position(&bytes[..err.valid_up_to()]);
+      Err(Error::Syntax(line, column, "invalid utf-8".to_owned()))
+    }
+  }
+}
+
+pub fn from_str(source: &str) -> Result<Snapshot, Error> {
+  let first = source
+    .lines()
+    .map(|line| strip_comment(line).trim())
+    .find(|line| !line.is_empty());
+  match first {
+    Some(line) if line.split_whitespace().next() == Some(HEADER) => parse_snapshot(source),
+    _ => {
+      let program = parse_text(source)?;
+      Ok(Snapshot::new(
+        0,
+        0,
+        program.len() as i64,
+        program.into_iter().enumerate().map(|(idx, val)| (idx as i64, val)),
+      ))
+    }
+  }
+}
+
+pub fn from_reader<R: Read>(mut reader: R) -> Result<Snapshot, Error> {
+  let mut bytes = Vec::new();
+  reader
+    .read_to_end(&mut bytes)
+    .map_err(|err| Error::Io(err.to_string()))?;
+  parse(&bytes)
+}
+
+pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Snapshot, Error> {
+  let bytes = std::fs::read(path.as_ref())
+    .map_err(|err| Error::Io(format!("{}: {}", path.as_ref().display(), err)))?;
+  parse(&bytes)
+}
+
+pub fn to_binary(program: &[i64]) -> Vec<u8> {
+  let mut bytes = MAGIC.to_vec();
+  write_varint(&mut bytes, program.len() as u64);
+  for value in program {
+    write_varint(&mut bytes, ((value << 1) ^ (value >> 63)) as u64);
+  }
+  bytes
+}
+
+pub fn to_text(snapshot: &Snapshot) -> String {
+  let mut out = format!(
+    "{} pc={} rb={} image={}",
+    HEADER,
+    snapshot.pc(),
+    snapshot.relative_base(),
+    snapshot.image()
+  );
+  if let Some(input) = snapshot.input() {
+    out.push_str(&format!(" input={}", input));
+  }
+  out.push('\n');
+  let mut next = None;
+  for (addr, value) in snapshot.cells() {
+    if next == Some(addr) {
+      out.push_str(&format!(" {}", value));
+    } else {
+      if next.is_some() {
+        out.push('\n');
+      }
+      out.push_str(&format!("{}: {}", addr, value));
+    }
+    next = Some(addr + 1);
+  }
+  if next.is_some() {
+    out.push('\n');
+  }
+  out
+}
+
+fn strip_comment(line: &str) -> &str {
+  match line.find('#') {
+    Some(pos) => &line[..pos],
+    None => line,
+  }
+}
+
+fn position(bytes: &[u8]) -> (usize, usize) {
+  let line = bytes.iter().filter(|b| **b == b'\n').count() + 1;
+  let column = bytes.iter().rev().take_while(|b| **b != b'\n').count() + 1;
+  (line, column)
+}
+
+fn words(line: &str) -> impl Iterator<Item = (usize, &str)> {
+  let mut column = 0;
+  line
+    .split(|c: char| c == ',' || c.is_whitespace())
+    .map(move |word| {
+      let start = column;
+      column += word.len() + 1;
+      (start + 1, word)
+    })
+    .filter(|(_, word)| !word.is_empty())
+}
+
+fn number(line: usize, column: usize, word: &str) -> Result<i64, Error> {
+  word
+    .parse()
+    .map_err(|_| Error::Syntax(line, column, format!("'{}' is not a number", word)))
+}
+
+fn parse_text(source: &str) -> Result<Vec<i64>, Error> {
+  let mut program = Vec::new();
+  for (idx, line) in source.lines().enumerate() {
+    for (column, word) in words(strip_comment(line)) {
+      program.push(number(idx + 1, column, word)?);
+    }
+  }
+  Ok(program)
+}
+
+fn parse_snapshot(source: &str) -> Result<Snapshot, Error> {
+  let mut header = None;
+  let mut cells = Vec::new();
+  for (idx, line) in source.lines().enumerate() {
+    let line_no = idx + 1;
+    let line = strip_comment(line);
+    if line.trim().is_empty() {
+      continue;
+    }
+    if header.is_none() {
+      header = Some(parse_header(line_no, line)?);
+      continue;
+    }
+    let colon = match line.find(':') {
+      Some(colon) => colon,
+      None => {
+        let column = line.len() - line.trim_start().len() + 1;
+        return Err(Error::Syntax(line_no, column, "expected 'address:'".to_owned()));
+      }
+    };
+    let mut address = None;
+    for (column, word) in words(&line[..colon]) {
+      if address.is_some() {
+        return Err(Error::Syntax(line_no, column, "expected ':'".to_owned()));
+      }
+      address = Some(number(line_no, column, word)?);
+    }
+    let addr = match address {
+      Some(addr) => addr,
+      None => return Err(Error::Syntax(line_no, colon + 1, "missing address".to_owned())),
+    };
+    for (offset, (column, word)) in words(&line[colon + 1..]).enumerate() {
+      cells.push((addr + offset as i64, number(line_no, colon + 1 + column, word)?));
+    }
+  }
+  let (pc, relative_base, image, input) = header.unwrap_or((0, 0, 0, None));
+  Ok(Snapshot::new(pc, relative_base, image, cells).with_input(input))
+}
+
+fn parse_header(line_no: usize, line: &str) -> Result<(i64, i64, i64, Option<i64>), Error> {
+  let (mut pc, mut relative_base, mut image, mut input) = (0, 0, 0, None);
+  for (column, word) in words(line).skip(1) {
+    let mut parts = word.splitn(2, '=');
+    let key = parts.next().unwrap_or("");
+    let value = match parts.next() {
+      Some(value) => number(line_no, column + key.len() + 1, value)?,
+      None => return Err(Error::Syntax(line_no, column, format!("expected '{}=value'", key))),
+    };
+    match key {
+      "pc" => pc = value,
+      "rb" => relative_base = value,
+      "image" => image = value,
+      "input" => input = Some(value),
+      _ => return Err(Error::Syntax(line_no, column, format!("unknown field '{}'", key))),
+    }
+  }
+  Ok((pc, relative_base, image, input))
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+  loop {
+    let byte = (value & 0x7f) as u8;
+    value >>= 7;
+    if value == 0 {
+      bytes.push(byte);
+      return;
+    }
+    bytes.push(byte | 0x80);
+  }
+}
+
+fn read_varint(bytes: &[u8], offset: &mut usize) -> Result<u64, Error> {
+  let start = *offset;
+  let mut value = 0u64;
+  let mut shift = 0;
+  loop {
+    let byte = match bytes.get(*offset) {
+      Some(byte) => *byte,
+      None => return Err(Error::Binary(start, "truncated value".to_owned())),
+    };
+    *offset += 1;
+    if shift > 63 {
+      return Err(Error::Binary(start, "value too large".to_owned()));
+    }
+    value |= u64::from(byte & 0x7f) << shift;
+    if byte & 0x80 == 0 {
+      return Ok(value);
+    }
+    shift += 7;
+  }
+}
+
+fn parse_binary(bytes: &[u8]) -> Result<Snapshot, Error> {
+  let mut offset = MAGIC.len();
+  let count = read_varint(bytes, &mut offset)?;
+  let mut program = Vec::new();
+  for _ in 0..count {
+    let raw = read_varint(bytes, &mut offset)?;
+    program.push((raw >> 1) as i64 ^ -((raw & 1) as i64));
+  }
+  if offset != bytes.len() {
+    return Err(Error::Binary(offset, "trailing bytes".to_owned()));
+  }
+  Ok(Snapshot::new(
+    0,
+    0,
+    program.len() as i64,
+    program.into_iter().enumerate().map(|(idx, val)| (idx as i64, val)),
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::event::Event;
+  use super::super::{run_to_end, Computer};
+  use super::*;
+
+  #[test]
+  fn text_1() {
+    let snapshot = from_str("# doubler\n3,0, 102 2\n0 0\t4 0\n99 # halt\n").unwrap();
+    assert_eq!(snapshot.program(), vec![3, 0, 102, 2, 0, 0, 4, 0, 99]);
+    assert_eq!(snapshot.image(), 9);
+  }
+  #[test]
+  fn text_error() {
+    assert_eq!(
+      from_str("1,2,3\n4, 5x,6"),
+      Err(Error::Syntax(2, 4, "'5x' is not a number".to_owned()))
+    );
+  }
+  #[test]
+  fn binary_1() {
+    let program = vec![1, -1, 1_125_899_906_842_624, i64::MIN, i64::MAX, 0];
+    let snapshot = parse(&to_binary(&program)).unwrap();
+    assert_eq!(snapshot.program(), program);
+    let mut truncated = to_binary(&program);
+    truncated.pop();
+    assert_eq!(
+      parse(&truncated),
+      Err(Error::Binary(truncated.len(), "truncated value".to_owned()))
+    );
+  }
+  #[test]
+  fn snapshot_1() {
+    let mut computer = Computer::load(0, &[3, 9, 4, 9, 109, 3, 99, 0, 0, 0]);
+    computer.edit_memory(1000, -7);
+    assert_eq!(run_to_end(&mut computer, &[12]), Ok(vec![12]));
+    let text = to_text(&computer.snapshot());
+    assert_eq!(
+      text,
+      "snapshot pc=6 rb=3 image=10\n0: 3 9 4 9 109 3 99 0 0 12\n1000: -7\n"
+    );
+    let loaded = from_reader(text.as_bytes()).unwrap();
+    assert_eq!(loaded, computer.snapshot());
+    let restored = Computer::restore(1, &loaded);
+    assert_eq!(restored.pc(), 6);
+    assert_eq!(restored.read_memory(1000), Some(-7));
+  }
+  #[test]
+  fn snapshot_input() {
+    let mut computer = Computer::load(0, &[3, 9, 1001, 9, 1, 9, 4, 9, 99, 0]);
+    assert!(matches!(computer.resume(), Event::Input { destination: 9, .. }));
+    let text = to_text(&computer.snapshot());
+    assert_eq!(
+      text,
+      "snapshot pc=2 rb=0 image=10 input=9\n0: 3 9 1001 9 1 9 4 9 99 0\n"
+    );
+    let mut restored = Computer::restore(1, &from_str(&text).unwrap());
+    assert_eq!(restored.want_input(), Some(1));
+    assert_eq!(run_to_end(&mut restored, &[41]), Ok(vec![42]));
+  }
+  #[test]
+  fn snapshot_error() {
+    assert_eq!(
+      from_str("snapshot pc=1 sp=2\n"),
+      Err(Error::Syntax(1, 15, "unknown field 'sp'".to_owned()))
+    );
+    assert_eq!(
+      from_str("snapshot\n0: 1 2\n4 5\n"),
+      Err(Error::Syntax(3, 1, "expected 'address:'".to_owned()))
+    );
+  }
+  #[test]
+  fn path_1() {
+    let snapshot = from_path("../day/02/input.csv").unwrap();
+    assert_eq!(
+      snapshot.program(),
+      helpers::loader::load_integer_row_list("../day/02/input.csv")[0]
+    );
+    assert!(matches!(from_path("../day/02/missing.csv"), Err(Error::Io(_))));
+  }
+}
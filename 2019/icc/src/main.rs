@@ -0,0 +1,185 @@
+use clap::{value_t, App, AppSettings, Arg, ArgMatches, SubCommand};
+use icc::dump::{Format, Snapshot};
+use icc::event::Event;
+use icc::gdb::Stub;
+use icc::loader;
+use icc::tui::Viewer;
+use icc::Computer;
+use tracing::Level;
+
+fn main() {
+  let program = Arg::with_name("program").required(true);
+  let input = Arg::with_name("input")
+    .long("input")
+    .short("i")
+    .takes_value(true)
+    .help("Comma separated input values");
+  let hex = Arg::with_name("hex").long("hex").short("x");
+  let matches = App::new("icc")
+    .setting(AppSettings::SubcommandRequired)
+    .arg(
+      Arg::with_name("verbose")
+        .long("verbose")
+        .short("v")
+        .multiple(true)
+        .global(true)
+        .help("Logs machine events, repeat for more detail"),
+    )
+    .subcommand(
+      SubCommand::with_name("dump")
+        .about("Runs a program and dumps its memory")
+        .args(&[program.clone(), input.clone(), hex.clone()])
+        .arg(
+          Arg::with_name("snapshot")
+            .long("snapshot")
+            .help("Writes the loadable snapshot format"),
+        ),
+    )
+    .subcommand(
+      SubCommand::with_name("diff")
+        .about("Diffs two snapshots, or a run of a program against its loaded image")
+        .args(&[program.clone(), input.clone(), hex])
+        .arg(
+          Arg::with_name("after")
+            .conflicts_with("input")
+            .help("Snapshot to diff against instead of running the program"),
+        ),
+    )
+    .subcommand(
+      SubCommand::with_name("gdb")
+        .about("Serves a program over the gdb remote protocol")
+        .args(&[program.clone(), input.clone()])
+        .arg(
+          Arg::with_name("port")
+            .long("port")
+            .short("p")
+            .takes_value(true)
+            .default_value("1234"),
+        ),
+    )
+    .subcommand(
+      SubCommand::with_name("tui")
+        .about("Shows a running program in the terminal")
+        .args(&[program, input])
+        .arg(
+          Arg::with_name("speed")
+            .long("speed")
+            .short("s")
+            .takes_value(true)
+            .default_value("1")
+            .help("Instructions per tick while running"),
+        ),
+    )
+    .get_matches();
+  let level = match matches.occurrences_of("verbose") {
+    0 => Level::WARN,
+    1 => Level::INFO,
+    2 => Level::DEBUG,
+    _ => Level::TRACE,
+  };
+  tracing_subscriber::fmt()
+    .with_max_level(level)
+    .with_writer(std::io::stderr)
+    .init();
+
+  match matches.subcommand() {
+    ("dump", Some(args)) => {
+      let (computer, format) = execute(args);
+      if args.is_present("snapshot") {
+        print!("{}", loader::to_text(&computer.snapshot()));
+      } else {
+        print!("{}", computer.snapshot().dump(format));
+      }
+    }
+    ("diff", Some(args)) => {
+      let before = load(args);
+      let after = match args.value_of("after") {
+        Some(path) => open(path),
+        None => execute(args).0.snapshot(),
+      };
+      print!("{}", before.diff(&after).show(format(args)));
+    }
+    ("gdb", Some(args)) => {
+      let port = args.value_of("port").unwrap();
+      let mut stub = Stub::new(Computer::restore(0, &load(args))).with_input(&inputs(args));
+      println!("waiting for gdb on 127.0.0.1:{}", port);
+      if let Err(err) = stub.listen(format!("127.0.0.1:{}", port)) {
+        eprintln!("gdb connection failed: {}", err);
+        std::process::exit(1);
+      }
+    }
+    ("tui", Some(args)) => {
+      let speed = value_t!(args, "speed", usize).unwrap_or_else(|err| err.exit());
+      let mut viewer = Viewer::new(Computer::restore(0, &load(args)))
+        .with_input(&inputs(args))
+        .with_speed(speed);
+      if let Err(err) = viewer.run() {
+        eprintln!("terminal failed: {}", err);
+        std::process::exit(1);
+      }
+    }
+    _ => unreachable!(),
+  }
+}
+
+fn load(args: &ArgMatches) -> Snapshot {
+  open(args.value_of("program").unwrap())
+}
+
+fn open(path: &str) -> Snapshot {
+  match loader::from_path(path) {
+    Ok(snapshot) => snapshot,
+    Err(err) => {
+      eprintln!("{}", err);
+      std::process::exit(1);
+    }
+  }
+}
+
+fn inputs(args: &ArgMatches) -> Vec<i64> {
+  args
+    .value_of("input")
+    .map(|values| {
+      values
+        .split(',')
+        .map(|v| v.trim().parse().expect("input should be numbers"))
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+fn format(args: &ArgMatches) -> Format {
+  if args.is_present("hex") {
+    Format::Hex
+  } else {
+    Format::Decimal
+  }
+}
+
+fn execute(args: &ArgMatches) -> (Computer, Format) {
+  let format = format(args);
+  let mut input = inputs(args);
+  input.reverse();
+  let mut computer = Computer::restore(0, &load(args));
+  loop {
+    match computer.resume() {
+      Event::Output { value, .. } => eprintln!("output: {}", value),
+      Event::Input { pc, .. } => match input.pop() {
+        Some(value) => {
+          computer.input_value(value);
+        }
+        None => {
+          eprintln!("stopped waiting for input at {}", pc);
+          break;
+        }
+      },
+      Event::Faulted { pc, error } => {
+        eprintln!("Faulted at {}: {}", pc, error);
+        break;
+      }
+      Event::Halted { .. } => break,
+      Event::Breakpoint { .. } | Event::BudgetExhausted { .. } => (),
+    }
+  }
+  (computer, format)
+}
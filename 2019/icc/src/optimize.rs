@@ -0,0 +1,786 @@
+use super::flow::*;
+use super::instruction::Instruction;
+use super::memory::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+const HOPS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rewrite {
+  Fold(i64),
+  Move(i64),
+  Thread(i64, i64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Optimized {
+  pub program: Vec<i64>,
+  pub pure: Vec<(i64, i64)>,
+  pub rewrites: Vec<Rewrite>,
+}
+
+impl Optimized {
+  pub fn is_pure(&self, addr: i64) -> bool {
+    self
+      .pure
+      .iter()
+      .any(|(from, to)| *from <= addr && addr < *to)
+  }
+}
+
+pub fn optimize(program: &[i64]) -> Optimized {
+  let (blocks, callbacks) = reachable(program);
+  let untouched = Optimized {
+    program: program.to_vec(),
+    pure: Vec::new(),
+    rewrites: Vec::new(),
+  };
+  let effects = match Effects::analyse(program, &blocks, &callbacks) {
+    Some(effects) => effects,
+    None => return untouched,
+  };
+
+  let (pure, _): (Vec<Block>, Vec<Block>) = blocks.into_iter().partition(|block| {
+    effects.reached.contains(&block.leader)
+      && !block.body.is_empty()
+      && !(block.leader..end(block)).any(|addr| effects.clobbers(addr))
+  });
+  let mut optimized = Optimized {
+    program: program.to_vec(),
+    pure: code_ranges(&pure),
+    rewrites: Vec::new(),
+  };
+  if effects.unknown_reads {
+    return optimized;
+  }
+
+  let jumps: BTreeMap<i64, i64> = pure
+    .iter()
+    .filter_map(|block| match block.body.first() {
+      Some((at, Instruction::JumpIfTrue((Value::Direct(c), Value::Direct(to))))) if *c != 0 => {
+        Some((*at, *to))
+      }
+      Some((at, Instruction::JumpIfFalse((Value::Direct(0), Value::Direct(to))))) => {
+        Some((*at, *to))
+      }
+      _ => None,
+    })
+    .collect();
+  for (at, instruction) in effects.listing.iter() {
+    let at = *at;
+    if (at..at + instruction.length()).any(|addr| effects.clobbers(addr) || effects.reads(addr)) {
+      continue;
+    }
+    let (rewrite, replacement) = match instruction {
+      Instruction::JumpIfTrue((_, Value::Direct(to)))
+      | Instruction::JumpIfFalse((_, Value::Direct(to))) => {
+        let mut target = *to;
+        for _ in 0..HOPS {
+          match jumps.get(&target) {
+            Some(next) if *next != target => target = *next,
+            _ => break,
+          }
+        }
+        if target == *to {
+          continue;
+        }
+        let mut cells = encode(instruction);
+        cells[2] = target;
+        (Rewrite::Thread(at, target), cells)
+      }
+      Instruction::Add((a, b, dest))
+      | Instruction::Multiply((a, b, dest))
+      | Instruction::LessThan((a, b, dest))
+      | Instruction::Equals((a, b, dest)) => match simplify(instruction, a, b) {
+        Some(Simple::Set(value)) => (
+          Rewrite::Fold(at),
+          encode(&Instruction::Add((
+            Value::Direct(value),
+            Value::Direct(0),
+            dest.clone(),
+          ))),
+        ),
+        Some(Simple::Move(src)) => (
+          Rewrite::Move(at),
+          encode(&Instruction::Add((src, Value::Direct(0), dest.clone()))),
+        ),
+        None => continue,
+      },
+      _ => continue,
+    };
+    let original = &program[at as usize..(at + instruction.length()) as usize];
+    if original != replacement.as_slice() {
+      optimized.program[at as usize..(at + instruction.length()) as usize]
+        .copy_from_slice(&replacement);
+      optimized.rewrites.push(rewrite);
+    }
+  }
+  optimized
+}
+
+// An inclusive range of addresses, open ends are i64::MIN and i64::MAX.
+type Span = (i64, i64);
+
+fn shift((lo, hi): Span, by: i64) -> Span {
+  let move_end = |end: i64| match end {
+    i64::MIN | i64::MAX => end,
+    end => end.saturating_add(by),
+  };
+  (move_end(lo), move_end(hi))
+}
+
+// Whether a jump is taken, when its condition is known.
+fn taken(instruction: &Instruction, known: &dyn Fn(&Value) -> Option<i64>) -> Option<bool> {
+  match instruction {
+    Instruction::JumpIfTrue((value, _)) => known(value).map(|c| c != 0),
+    Instruction::JumpIfFalse((value, _)) => known(value).map(|c| c == 0),
+    _ => None,
+  }
+}
+
+// Constants the program moves around unchanged, such as return addresses.
+fn constants(blocks: &[Block]) -> BTreeSet<i64> {
+  blocks
+    .iter()
+    .flat_map(|block| block.body.iter())
+    .filter_map(|(_, instruction)| match instruction {
+      Instruction::Add((Value::Direct(constant), Value::Direct(0), _))
+      | Instruction::Add((Value::Direct(0), Value::Direct(constant), _))
+      | Instruction::Multiply((Value::Direct(constant), Value::Direct(1), _))
+      | Instruction::Multiply((Value::Direct(1), Value::Direct(constant), _)) => Some(*constant),
+      _ => None,
+    })
+    .collect()
+}
+
+// Follows the control flow from the start. With jumps through pointers in
+// play, constants that point at decodable cells outside the known code are
+// taken as further entries, the callbacks.
+fn reachable(program: &[i64]) -> (Vec<Block>, Vec<i64>) {
+  let mut entries = vec![0];
+  loop {
+    let blocks = blocks_from(program, &entries);
+    let indirect = blocks
+      .iter()
+      .flat_map(|block| block.body.iter())
+      .any(|(_, instruction)| {
+        matches!(
+          instruction,
+          Instruction::JumpIfTrue((_, Value::Stored(_)))
+            | Instruction::JumpIfFalse((_, Value::Stored(_)))
+        )
+      });
+    if !indirect {
+      return (blocks, Vec::new());
+    }
+    let code = code_ranges(&blocks);
+    let found: Vec<i64> = constants(&blocks)
+      .into_iter()
+      .filter(|at| {
+        (0..program.len() as i64).contains(at)
+          && !covered(&code, *at)
+          && decode(program, *at).is_some()
+      })
+      .collect();
+    if found.is_empty() {
+      entries.remove(0);
+      return (blocks, entries);
+    }
+    entries.extend(found);
+  }
+}
+
+// The relative base on entry to each reachable block, widened to an open end
+// wherever it keeps growing or shrinking around a loop. Code after an
+// unconditional jump is only reached if its address is handed out as a
+// constant, the way a call passes its return address.
+fn relative_bases(
+  program: &[i64],
+  blocks: &[Block],
+  callbacks: &[i64],
+  known: &dyn Fn(&Value) -> Option<i64>,
+) -> BTreeMap<i64, Span> {
+  let by_leader: BTreeMap<i64, &Block> = blocks.iter().map(|block| (block.leader, block)).collect();
+  let constants = constants(blocks);
+  let mut bases = BTreeMap::new();
+  let mut todo = vec![(0, (0, 0))];
+  while let Some((leader, base)) = todo.pop() {
+    let block = match by_leader.get(&leader) {
+      Some(block) => block,
+      None => continue,
+    };
+    let base = match bases.get(&leader) {
+      Some(&(lo, hi)) => {
+        let (new_lo, new_hi) = base;
+        let joined = (
+          if new_lo < lo { i64::MIN } else { lo },
+          if new_hi > hi { i64::MAX } else { hi },
+        );
+        if joined == (lo, hi) {
+          continue;
+        }
+        joined
+      }
+      None => base,
+    };
+    bases.insert(leader, base);
+    let mut out = base;
+    for (_, instruction) in block.body.iter() {
+      if taken(instruction, known) == Some(false) {
+        continue;
+      }
+      match instruction {
+        Instruction::ReduceAbsoluteRelative(Value::Direct(by)) => out = shift(out, *by),
+        Instruction::ReduceAbsoluteRelative(_) => out = (i64::MIN, i64::MAX),
+        Instruction::JumpIfTrue((_, Value::Direct(to)))
+        | Instruction::JumpIfFalse((_, Value::Direct(to))) => todo.push((*to, out)),
+        Instruction::JumpIfTrue((_, Value::Stored(pointer)))
+        | Instruction::JumpIfFalse((_, Value::Stored(pointer))) => {
+          if let Some(to) = program.get(*pointer as usize) {
+            todo.push((*to, out));
+          }
+          todo.extend(callbacks.iter().map(|to| (*to, out)));
+        }
+        _ => (),
+      }
+    }
+    match (&block.exit, block.body.last()) {
+      (Exit::Fallthrough(next), Some((_, last)))
+        if taken(last, known) == Some(true) && !constants.contains(next) => {}
+      (Exit::Fallthrough(next), _) => todo.push((*next, out)),
+      _ => (),
+    }
+  }
+  bases
+}
+
+struct Effects {
+  listing: Vec<(i64, Instruction)>,
+  writes: BTreeSet<i64>,
+  reads: BTreeSet<i64>,
+  write_spans: Vec<Span>,
+  read_spans: Vec<Span>,
+  pointers: Vec<i64>,
+  stops: Vec<i64>,
+  code: Vec<(i64, i64)>,
+  reached: BTreeSet<i64>,
+  unknown_reads: bool,
+}
+
+impl Effects {
+  // Jumps whose condition reads a cell that nothing writes always go the same
+  // way, which can leave code and its writes unreachable. Each round prunes
+  // with the writes of the previous one until nothing changes.
+  fn analyse(program: &[i64], blocks: &[Block], callbacks: &[i64]) -> Option<Effects> {
+    let mut effects = Effects::of(program, blocks, callbacks, None)?;
+    loop {
+      if !effects.is_static() {
+        return None;
+      }
+      let next = Effects::of(program, blocks, callbacks, Some(&effects))?;
+      if next.listing.len() == effects.listing.len() {
+        return Some(effects);
+      }
+      effects = next;
+    }
+  }
+  fn of(
+    program: &[i64],
+    blocks: &[Block],
+    callbacks: &[i64],
+    previous: Option<&Effects>,
+  ) -> Option<Effects> {
+    let known = |value: &Value| match value {
+      Value::Direct(c) => Some(*c),
+      Value::Stored(addr) => previous
+        .filter(|effects| !effects.clobbers(*addr))
+        .and_then(|_| program.get(*addr as usize).cloned()),
+      Value::Relative(_) => None,
+    };
+    let bases = relative_bases(program, blocks, callbacks, &known);
+    let mut jump_pointers = Vec::new();
+    let mut effects = Effects {
+      listing: Vec::new(),
+      writes: BTreeSet::new(),
+      reads: BTreeSet::new(),
+      write_spans: Vec::new(),
+      read_spans: Vec::new(),
+      pointers: Vec::new(),
+      stops: Vec::new(),
+      code: code_ranges(blocks),
+      reached: bases.keys().cloned().collect(),
+      unknown_reads: false,
+    };
+    for block in blocks {
+      let mut base = match bases.get(&block.leader) {
+        Some(base) => *base,
+        None => continue,
+      };
+      if let Exit::Stop(at) = block.exit {
+        effects.stops.push(at);
+      }
+      for (at, instruction) in block.body.iter() {
+        match instruction {
+          Instruction::Add((a, b, dest))
+          | Instruction::Multiply((a, b, dest))
+          | Instruction::LessThan((a, b, dest))
+          | Instruction::Equals((a, b, dest)) => {
+            effects.read(a, base);
+            effects.read(b, base);
+            effects.write(dest, base);
+          }
+          Instruction::JumpIfTrue((value, to)) | Instruction::JumpIfFalse((value, to)) => {
+            effects.read(value, base);
+            match to {
+              Value::Direct(_) => (),
+              Value::Relative(_) => effects.read(to, base),
+              Value::Stored(pointer) => {
+                effects.reads.insert(*pointer);
+                jump_pointers.push(*pointer);
+              }
+            }
+          }
+          Instruction::Output(value) => effects.read(value, base),
+          Instruction::ReduceAbsoluteRelative(value) => {
+            effects.read(value, base);
+            base = match value {
+              Value::Direct(by) => shift(base, *by),
+              _ => (i64::MIN, i64::MAX),
+            };
+          }
+          Instruction::Input(dest) => effects.write(dest, base),
+          Instruction::Halt => (),
+        }
+        effects.listing.push((*at, instruction.clone()));
+      }
+    }
+    // Indirect writes and jumps go where their pointer cell says, as long as
+    // nothing can change the pointer.
+    let target = |pointer: i64| program.get(pointer as usize).cloned().unwrap_or(0);
+    for pointer in std::mem::take(&mut effects.pointers) {
+      if effects.clobbers(pointer) {
+        return None;
+      }
+      effects.writes.insert(target(pointer));
+    }
+    for pointer in jump_pointers {
+      if !effects.clobbers(pointer) && !bases.contains_key(&target(pointer)) {
+        return None;
+      }
+    }
+    Some(effects)
+  }
+  fn read(&mut self, value: &Value, base: Span) {
+    match value {
+      Value::Direct(_) => (),
+      Value::Stored(addr) => {
+        self.reads.insert(*addr);
+      }
+      Value::Relative(offset) => self.read_spans.push(shift(base, *offset)),
+    }
+  }
+  fn write(&mut self, dest: &Value, base: Span) {
+    match dest {
+      Value::Direct(addr) => {
+        self.writes.insert(*addr);
+      }
+      Value::Stored(pointer) => {
+        self.reads.insert(*pointer);
+        self.pointers.push(*pointer);
+      }
+      Value::Relative(offset) => self.write_spans.push(shift(base, *offset)),
+    }
+  }
+  fn clobbers(&self, addr: i64) -> bool {
+    self.writes.contains(&addr)
+      || self
+        .write_spans
+        .iter()
+        .any(|(lo, hi)| *lo <= addr && addr <= *hi)
+  }
+  fn reads(&self, addr: i64) -> bool {
+    self.reads.contains(&addr)
+      || self
+        .read_spans
+        .iter()
+        .any(|(lo, hi)| *lo <= addr && addr <= *hi)
+  }
+  // Where an address operand rewritten by the program can point. The program
+  // has to compute it as a constant plus an index, and the index is taken to
+  // stay inside the data region around that constant.
+  fn reach(&self, cell: i64) -> Option<Span> {
+    let mut reach: Option<Span> = None;
+    for (_, instruction) in self.listing.iter() {
+      let base = match instruction {
+        Instruction::Add((Value::Direct(base), _, Value::Direct(dest)))
+        | Instruction::Add((_, Value::Direct(base), Value::Direct(dest)))
+          if *dest == cell =>
+        {
+          *base
+        }
+        Instruction::Add((_, _, Value::Direct(dest)))
+        | Instruction::Multiply((_, _, Value::Direct(dest)))
+        | Instruction::LessThan((_, _, Value::Direct(dest)))
+        | Instruction::Equals((_, _, Value::Direct(dest)))
+        | Instruction::Input(Value::Direct(dest))
+          if *dest == cell =>
+        {
+          return None
+        }
+        _ => continue,
+      };
+      if base < 0 || covered(&self.code, base) {
+        return None;
+      }
+      let from = self
+        .code
+        .iter()
+        .map(|(_, to)| *to)
+        .filter(|to| *to <= base)
+        .max()
+        .unwrap_or(0);
+      let to = self
+        .code
+        .iter()
+        .map(|(from, _)| *from - 1)
+        .filter(|to| *to >= base)
+        .min()
+        .unwrap_or(i64::MAX);
+      reach = Some(match reach {
+        Some((lo, hi)) => (lo.min(from), hi.max(to)),
+        None => (from, to),
+      });
+    }
+    reach
+  }
+  // Code that only becomes decodable once it is written, or whose opcodes and
+  // jump targets change, cannot be analysed. Rewritten address operands widen
+  // the reads and writes to their data region, or to anything when unbounded.
+  fn is_static(&mut self) -> bool {
+    let mut seen = BTreeSet::new();
+    loop {
+      let mut patched = Vec::new();
+      for (at, instruction) in self.listing.iter() {
+        let dynamic = |offset: i64| self.clobbers(at + offset);
+        let fixed = match instruction {
+          Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_) => !dynamic(0) && !dynamic(2),
+          _ => !dynamic(0),
+        };
+        if !fixed {
+          return false;
+        }
+        let operands = match instruction {
+          Instruction::Add((a, b, dest))
+          | Instruction::Multiply((a, b, dest))
+          | Instruction::LessThan((a, b, dest))
+          | Instruction::Equals((a, b, dest)) => {
+            vec![(1, a, false), (2, b, false), (3, dest, true)]
+          }
+          Instruction::JumpIfTrue((value, to)) | Instruction::JumpIfFalse((value, to)) => {
+            vec![(1, value, false), (2, to, false)]
+          }
+          Instruction::ReduceAbsoluteRelative(value) | Instruction::Output(value) => {
+            vec![(1, value, false)]
+          }
+          Instruction::Input(dest) => vec![(1, dest, true)],
+          Instruction::Halt => Vec::new(),
+        };
+        for (offset, value, dest) in operands {
+          if dynamic(offset) && !seen.contains(&(at + offset)) {
+            patched.push((at + offset, value.clone(), dest));
+          }
+        }
+      }
+      if patched.is_empty() {
+        break;
+      }
+      for (cell, value, dest) in patched {
+        seen.insert(cell);
+        let reach = match (value, dest) {
+          (Value::Direct(_), false) => continue,
+          (Value::Stored(_), false) | (Value::Direct(_), true) => self.reach(cell),
+          _ => None,
+        };
+        match (reach, dest) {
+          (Some(span), true) => self.write_spans.push(span),
+          (Some(span), false) => self.read_spans.push(span),
+          (None, true) => return false,
+          (None, false) => self.unknown_reads = true,
+        }
+      }
+    }
+    !self.stops.iter().any(|at| self.clobbers(*at))
+  }
+}
+
+fn covered(code: &[(i64, i64)], addr: i64) -> bool {
+  code.iter().any(|(from, to)| *from <= addr && addr < *to)
+}
+
+enum Simple {
+  Set(i64),
+  Move(Value),
+}
+
+fn simplify(instruction: &Instruction, a: &Value, b: &Value) -> Option<Simple> {
+  let safe = |value: &Value| match value {
+    Value::Direct(_) => true,
+    Value::Stored(addr) => *addr >= 0,
+    Value::Relative(_) => false,
+  };
+  match (instruction, a, b) {
+    (Instruction::Add(_), Value::Direct(a), Value::Direct(b)) => a.checked_add(*b).map(Simple::Set),
+    (Instruction::Multiply(_), Value::Direct(a), Value::Direct(b)) => {
+      a.checked_mul(*b).map(Simple::Set)
+    }
+    (Instruction::LessThan(_), Value::Direct(a), Value::Direct(b)) => {
+      Some(Simple::Set(if a < b { 1 } else { 0 }))
+    }
+    (Instruction::Equals(_), Value::Direct(a), Value::Direct(b)) => {
+      Some(Simple::Set(if a == b { 1 } else { 0 }))
+    }
+    (Instruction::Multiply(_), Value::Direct(0), other)
+    | (Instruction::Multiply(_), other, Value::Direct(0))
+      if safe(other) =>
+    {
+      Some(Simple::Set(0))
+    }
+    (Instruction::Multiply(_), Value::Direct(1), other)
+    | (Instruction::Multiply(_), other, Value::Direct(1))
+    | (Instruction::Add(_), Value::Direct(0), other) => Some(Simple::Move(other.clone())),
+    _ => None,
+  }
+}
+
+fn end(block: &Block) -> i64 {
+  block
+    .body
+    .last()
+    .map(|(at, instruction)| at + instruction.length())
+    .unwrap_or(block.leader)
+}
+
+fn source_mode(value: &Value) -> i64 {
+  match value {
+    Value::Stored(_) => 0,
+    Value::Direct(_) => 1,
+    Value::Relative(_) => 2,
+  }
+}
+
+fn dest_mode(value: &Value) -> i64 {
+  match value {
+    Value::Direct(_) => 0,
+    Value::Stored(_) => 1,
+    Value::Relative(_) => 2,
+  }
+}
+
+fn raw(value: &Value) -> i64 {
+  match value {
+    Value::Direct(v) | Value::Stored(v) | Value::Relative(v) => *v,
+  }
+}
+
+pub fn encode(instruction: &Instruction) -> Vec<i64> {
+  let triplet = |op: i64, (a, b, dest): &(Value, Value, Value)| {
+    vec![
+      op + 100 * source_mode(a) + 1000 * source_mode(b) + 10_000 * dest_mode(dest),
+      raw(a),
+      raw(b),
+      raw(dest),
+    ]
+  };
+  let duplex = |op: i64, (value, to): &(Value, Value)| {
+    vec![
+      op + 100 * source_mode(value) + 1000 * source_mode(to),
+      raw(value),
+      raw(to),
+    ]
+  };
+  match instruction {
+    Instruction::Add(params) => triplet(1, params),
+    Instruction::Multiply(params) => triplet(2, params),
+    Instruction::LessThan(params) => triplet(7, params),
+    Instruction::Equals(params) => triplet(8, params),
+    Instruction::JumpIfTrue(params) => duplex(5, params),
+    Instruction::JumpIfFalse(params) => duplex(6, params),
+    Instruction::Input(dest) => match dest {
+      Value::Direct(addr) => vec![3, *addr],
+      other => vec![3 + 100 * source_mode(other), raw(other)],
+    },
+    Instruction::Output(value) => vec![4 + 100 * source_mode(value), raw(value)],
+    Instruction::ReduceAbsoluteRelative(value) => vec![9 + 100 * source_mode(value), raw(value)],
+    Instruction::Halt => vec![99],
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::*;
+  use super::*;
+
+  fn equivalent(program: &[i64], input: &[i64]) -> Optimized {
+    let optimized = optimize(program);
+    let mut plain = Computer::load(0, program);
+    let mut fast = Computer::load_optimized(0, &optimized);
+    assert_eq!(run_to_end(&mut plain, input), run_to_end(&mut fast, input));
+    assert_eq!(plain.pc(), fast.pc());
+    assert_eq!(plain.relative_base(), fast.relative_base());
+    let changed: Vec<i64> = optimized
+      .program
+      .iter()
+      .zip(program.iter())
+      .enumerate()
+      .filter(|(_, (a, b))| a != b)
+      .map(|(idx, _)| idx as i64)
+      .collect();
+    let before = plain.snapshot();
+    let after = fast.snapshot();
+    before
+      .cells()
+      .filter(|(addr, _)| !changed.contains(addr))
+      .for_each(|(addr, value)| assert_eq!(after.get(addr), Some(value), "cell {}", addr));
+    optimized
+  }
+
+  #[test]
+  fn encode_roundtrip() {
+    let program = [21101, 3, -4, 7, 1206, 5, 9, 203, 2, 109, -1, 99];
+    let mut pc = 0;
+    while let Some((instruction, length)) = decode(&program, pc) {
+      assert_eq!(
+        encode(&instruction).as_slice(),
+        &program[pc as usize..(pc + length) as usize]
+      );
+      pc += length;
+      if pc as usize >= program.len() {
+        break;
+      }
+    }
+  }
+  #[test]
+  fn fold_and_move() {
+    let program = vec![
+      1102, 6, 7, 21, 1002, 21, 1, 22, 1001, 22, 0, 23, 1107, 1, 2, 24, 4, 23, 4, 24, 99, 0, 0, 0,
+      0,
+    ];
+    let mut optimized = equivalent(&program, &[]);
+    optimized.program.truncate(16);
+    assert_eq!(
+      optimized.program,
+      vec![1101, 42, 0, 21, 1001, 21, 0, 22, 1001, 22, 0, 23, 1101, 1, 0, 24]
+    );
+    assert_eq!(
+      optimized.rewrites,
+      vec![Rewrite::Fold(0), Rewrite::Move(4), Rewrite::Fold(12)]
+    );
+    assert!(optimized.is_pure(0));
+  }
+  #[test]
+  fn thread_jumps() {
+    let program = vec![
+      3, 20, 1005, 20, 9, 104, 0, 99, 0, 1105, 1, 12, 1106, 0, 15, 104, 1, 99, 0, 0, 0,
+    ];
+    let optimized = equivalent(&program, &[1]);
+    equivalent(&program, &[0]);
+    assert_eq!(
+      optimized.rewrites,
+      vec![Rewrite::Thread(2, 15), Rewrite::Thread(9, 15)]
+    );
+  }
+  #[test]
+  fn self_modifying() {
+    let program = vec![1101, 1, 1, 5, 1101, 1, 1, 9, 99, 0];
+    let optimized = equivalent(&program, &[]);
+    assert_eq!(optimized.program, vec![1101, 2, 0, 5, 1101, 1, 1, 9, 99, 0]);
+    assert_eq!(optimized.rewrites, vec![Rewrite::Fold(0)]);
+    assert!(optimized.pure.is_empty());
+    let program = vec![1101, 4, 0, 4, 104, 5, 99];
+    let optimized = equivalent(&program, &[]);
+    assert!(optimized.rewrites.is_empty());
+  }
+  #[test]
+  fn data_reads() {
+    let program = vec![1102, 2, 3, 8, 4, 1, 99, 0, 0];
+    let optimized = equivalent(&program, &[]);
+    assert!(optimized.rewrites.is_empty());
+    assert!(optimized.is_pure(0));
+  }
+  #[test]
+  fn puzzle_inputs() {
+    let day05 = helpers::loader::load_integer_row_list("../day/05/input.csv")[0].clone();
+    equivalent(&day05, &[1]);
+    equivalent(&day05, &[5]);
+    let day09 = helpers::loader::load_integer_row_list("../day/09/input.csv")[0].clone();
+    equivalent(&day09, &[1]);
+    let day07 = helpers::loader::load_integer_row_list("../day/07/input.csv")[0].clone();
+    for phase in 0..5 {
+      equivalent(&day07, &[phase, 17]);
+    }
+  }
+  #[test]
+  fn regions() {
+    // Writes through the relative base and through an operand computed as
+    // 23 plus an index stay out of the code, which can still be rewritten.
+    let program = vec![
+      109, 100, 21102, 3, 1, 0, 1001, 25, 23, 13, 1102, 2, 1, 23, 204, 0, 4, 24, 1102, 6, 7, 26,
+      99, 0, 0, 1,
+    ];
+    let optimized = equivalent(&program, &[]);
+    assert_eq!(
+      optimized.rewrites,
+      vec![Rewrite::Fold(2), Rewrite::Fold(18)]
+    );
+    assert!(!optimized.is_pure(0));
+    assert!(optimized.is_pure(18));
+    let program = vec![3, 5, 1101, 1, 1, 0, 4, 0, 99];
+    let optimized = equivalent(&program, &[7]);
+    assert!(optimized.rewrites.is_empty());
+  }
+  #[test]
+  fn hull_painter() {
+    let program = helpers::loader::load_integer_row_list("../day/11/input.csv")[0].clone();
+    let optimized = optimize(&program);
+    assert_eq!(optimized.rewrites.len(), 28);
+    assert!(!optimized.pure.is_empty());
+    let paint = |mut computer: Computer| {
+      let mut hull = HashMap::new();
+      let (mut x, mut y, mut dx, mut dy) = (0i64, 0i64, 0i64, -1i64);
+      let mut pending = Vec::new();
+      loop {
+        match computer.resume() {
+          Event::Input { .. } => {
+            computer.input_value(*hull.get(&(x, y)).unwrap_or(&0));
+          }
+          Event::Output { value, .. } => pending.push(value),
+          Event::Halted { .. } => break,
+          event => panic!("unexpected {:?}", event),
+        }
+        if let [color, turn] = pending[..] {
+          hull.insert((x, y), color);
+          let (ndx, ndy) = if turn == 0 { (dy, -dx) } else { (-dy, dx) };
+          dx = ndx;
+          dy = ndy;
+          x += dx;
+          y += dy;
+          pending.clear();
+        }
+      }
+      let mut hull: Vec<((i64, i64), i64)> = hull.into_iter().collect();
+      hull.sort();
+      (hull, computer.pc(), computer.relative_base())
+    };
+    assert_eq!(
+      paint(Computer::load(0, &program)),
+      paint(Computer::load_optimized(0, &optimized))
+    );
+  }
+  #[test]
+  fn pure_invalidation() {
+    let program = vec![1102, 6, 7, 9, 4, 9, 99, 0, 0, 0];
+    let mut computer = Computer::load_optimized(0, &optimize(&program));
+    computer.edit_memory(4, 104);
+    assert_eq!(run_to_end(&mut computer, &[]), Ok(vec![9]));
+  }
+}
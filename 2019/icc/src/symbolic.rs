@@ -0,0 +1,706 @@
+use super::dump::Snapshot;
+use super::error;
+use super::instruction::Instruction;
+use super::memory::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::ops::RangeInclusive;
+use tracing::{debug, trace};
+
+const LIMIT: usize = 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Symbol {
+  Cell(i64),
+  Input(usize),
+}
+impl std::fmt::Display for Symbol {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Symbol::Cell(addr) => write!(fmt, "m{}", addr),
+      Symbol::Input(idx) => write!(fmt, "in{}", idx),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+  Decode(i64, error::Error),
+  Code(i64),
+  Branch(i64, Expr),
+  Address(i64),
+  Limit(i64),
+  NotPolynomial,
+  Unbounded(Symbol),
+}
+impl std::fmt::Display for Error {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Error::Decode(pc, error) => write!(fmt, "Failed to decode at {}: {}", pc, error),
+      Error::Code(pc) => write!(fmt, "Symbolic instruction at {}.", pc),
+      Error::Branch(pc, cond) => write!(fmt, "Symbolic branch at {} on {}.", pc, cond),
+      Error::Address(pc) => write!(fmt, "Symbolic address used at {}.", pc),
+      Error::Limit(pc) => write!(fmt, "Step limit reached at {}.", pc),
+      Error::NotPolynomial => write!(fmt, "Expression is not a polynomial."),
+      Error::Unbounded(symbol) => write!(fmt, "No domain given for {}.", symbol),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Polynomial {
+  terms: BTreeMap<Vec<Symbol>, i64>,
+}
+
+impl Polynomial {
+  pub fn constant(value: i64) -> Polynomial {
+    let mut poly = Polynomial::default();
+    poly.insert(Vec::new(), value);
+    poly
+  }
+  pub fn symbol(symbol: Symbol) -> Polynomial {
+    let mut poly = Polynomial::default();
+    poly.insert(vec![symbol], 1);
+    poly
+  }
+  pub fn as_const(&self) -> Option<i64> {
+    match self.terms.len() {
+      0 => Some(0),
+      1 => self.terms.get(&Vec::new()).cloned(),
+      _ => None,
+    }
+  }
+  pub fn coefficient(&self, monomial: &[Symbol]) -> i64 {
+    let mut monomial = monomial.to_vec();
+    monomial.sort();
+    self.terms.get(&monomial).cloned().unwrap_or(0)
+  }
+  pub fn symbols(&self) -> BTreeSet<Symbol> {
+    self.terms.keys().flatten().cloned().collect()
+  }
+  pub fn degree(&self) -> usize {
+    self.terms.keys().map(Vec::len).max().unwrap_or(0)
+  }
+  pub fn degree_in(&self, symbol: Symbol) -> usize {
+    self
+      .terms
+      .keys()
+      .map(|monomial| monomial.iter().filter(|s| **s == symbol).count())
+      .max()
+      .unwrap_or(0)
+  }
+  pub fn add(&self, other: &Polynomial) -> Polynomial {
+    let mut poly = self.clone();
+    for (monomial, coefficient) in &other.terms {
+      poly.insert(monomial.clone(), *coefficient);
+    }
+    poly
+  }
+  pub fn mul(&self, other: &Polynomial) -> Polynomial {
+    let mut poly = Polynomial::default();
+    for (lhs, a) in &self.terms {
+      for (rhs, b) in &other.terms {
+        let mut monomial = lhs.clone();
+        monomial.extend(rhs);
+        monomial.sort();
+        poly.insert(monomial, a.wrapping_mul(*b));
+      }
+    }
+    poly
+  }
+  pub fn substitute(&self, values: &HashMap<Symbol, i64>) -> Polynomial {
+    let mut poly = Polynomial::default();
+    for (monomial, coefficient) in &self.terms {
+      let mut factor = *coefficient;
+      let mut rest = Vec::new();
+      for symbol in monomial {
+        match values.get(symbol) {
+          Some(value) => factor = factor.wrapping_mul(*value),
+          None => rest.push(*symbol),
+        }
+      }
+      poly.insert(rest, factor);
+    }
+    poly
+  }
+  pub fn eval(&self, values: &HashMap<Symbol, i64>) -> Option<i64> {
+    self.substitute(values).as_const()
+  }
+  fn insert(&mut self, monomial: Vec<Symbol>, coefficient: i64) {
+    let sum = self
+      .terms
+      .get(&monomial)
+      .cloned()
+      .unwrap_or(0)
+      .wrapping_add(coefficient);
+    if sum == 0 {
+      self.terms.remove(&monomial);
+    } else {
+      self.terms.insert(monomial, sum);
+    }
+  }
+}
+impl std::fmt::Display for Polynomial {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    if self.terms.is_empty() {
+      return write!(fmt, "0");
+    }
+    let mut terms: Vec<_> = self.terms.iter().collect();
+    terms.sort_by_key(|(monomial, _)| std::cmp::Reverse(monomial.len()));
+    for (idx, (monomial, coefficient)) in terms.into_iter().enumerate() {
+      let sign = if *coefficient < 0 { "-" } else { "+" };
+      match (idx, sign) {
+        (0, "-") => write!(fmt, "-")?,
+        (0, _) => (),
+        _ => write!(fmt, " {} ", sign)?,
+      }
+      let magnitude = coefficient.unsigned_abs();
+      let factors: Vec<String> = monomial.iter().map(Symbol::to_string).collect();
+      match (magnitude, factors.is_empty()) {
+        (_, true) => write!(fmt, "{}", magnitude)?,
+        (1, false) => write!(fmt, "{}", factors.join("*"))?,
+        _ => write!(fmt, "{}*{}", magnitude, factors.join("*"))?,
+      }
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+  Poly(Polynomial),
+  Add(Box<Expr>, Box<Expr>),
+  Mul(Box<Expr>, Box<Expr>),
+  Less(Box<Expr>, Box<Expr>),
+  Equals(Box<Expr>, Box<Expr>),
+  Load(Box<Expr>),
+}
+
+impl Expr {
+  pub fn constant(value: i64) -> Expr {
+    Expr::Poly(Polynomial::constant(value))
+  }
+  pub fn symbol(symbol: Symbol) -> Expr {
+    Expr::Poly(Polynomial::symbol(symbol))
+  }
+  pub fn sum(lhs: Expr, rhs: Expr) -> Expr {
+    match (lhs, rhs) {
+      (Expr::Poly(lhs), Expr::Poly(rhs)) => Expr::Poly(lhs.add(&rhs)),
+      (lhs, rhs) => Expr::Add(Box::new(lhs), Box::new(rhs)),
+    }
+  }
+  pub fn product(lhs: Expr, rhs: Expr) -> Expr {
+    match (lhs, rhs) {
+      (Expr::Poly(lhs), Expr::Poly(rhs)) => Expr::Poly(lhs.mul(&rhs)),
+      (lhs, rhs) => Expr::Mul(Box::new(lhs), Box::new(rhs)),
+    }
+  }
+  pub fn less(lhs: Expr, rhs: Expr) -> Expr {
+    match (lhs.as_const(), rhs.as_const()) {
+      (Some(lhs), Some(rhs)) => Expr::constant((lhs < rhs) as i64),
+      _ if lhs == rhs => Expr::constant(0),
+      _ => Expr::Less(Box::new(lhs), Box::new(rhs)),
+    }
+  }
+  pub fn equals(lhs: Expr, rhs: Expr) -> Expr {
+    match (lhs.as_const(), rhs.as_const()) {
+      (Some(lhs), Some(rhs)) => Expr::constant((lhs == rhs) as i64),
+      _ if lhs == rhs => Expr::constant(1),
+      _ => Expr::Equals(Box::new(lhs), Box::new(rhs)),
+    }
+  }
+  pub fn load(addr: Expr) -> Expr {
+    Expr::Load(Box::new(addr))
+  }
+  pub fn as_const(&self) -> Option<i64> {
+    self.polynomial().and_then(Polynomial::as_const)
+  }
+  pub fn polynomial(&self) -> Option<&Polynomial> {
+    match self {
+      Expr::Poly(poly) => Some(poly),
+      _ => None,
+    }
+  }
+  pub fn eval(&self, values: &HashMap<Symbol, i64>) -> Option<i64> {
+    match self {
+      Expr::Poly(poly) => poly.eval(values),
+      Expr::Add(lhs, rhs) => Some(lhs.eval(values)?.wrapping_add(rhs.eval(values)?)),
+      Expr::Mul(lhs, rhs) => Some(lhs.eval(values)?.wrapping_mul(rhs.eval(values)?)),
+      Expr::Less(lhs, rhs) => Some((lhs.eval(values)? < rhs.eval(values)?) as i64),
+      Expr::Equals(lhs, rhs) => Some((lhs.eval(values)? == rhs.eval(values)?) as i64),
+      Expr::Load(_) => None,
+    }
+  }
+}
+impl std::fmt::Display for Expr {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Expr::Poly(poly) => write!(fmt, "{}", poly),
+      Expr::Add(lhs, rhs) => write!(fmt, "({} + {})", lhs, rhs),
+      Expr::Mul(lhs, rhs) => write!(fmt, "({} * {})", lhs, rhs),
+      Expr::Less(lhs, rhs) => write!(fmt, "({} < {})", lhs, rhs),
+      Expr::Equals(lhs, rhs) => write!(fmt, "({} == {})", lhs, rhs),
+      Expr::Load(addr) => write!(fmt, "[{}]", addr),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Executor {
+  memory: HashMap<i64, Expr>,
+  pc: i64,
+  relative_base: i64,
+  inputs: VecDeque<Expr>,
+  read: usize,
+  outputs: Vec<Expr>,
+}
+
+impl Executor {
+  pub fn load(program: &[i64]) -> Executor {
+    Executor {
+      memory: program
+        .iter()
+        .enumerate()
+        .map(|(addr, val)| (addr as i64, Expr::constant(*val)))
+        .collect(),
+      pc: 0,
+      relative_base: 0,
+      inputs: VecDeque::new(),
+      read: 0,
+      outputs: Vec::new(),
+    }
+  }
+  pub fn restore(snapshot: &Snapshot) -> Executor {
+    Executor {
+      memory: snapshot
+        .cells()
+        .map(|(addr, val)| (addr, Expr::constant(val)))
+        .collect(),
+      pc: snapshot.pc(),
+      relative_base: snapshot.relative_base(),
+      inputs: VecDeque::new(),
+      read: 0,
+      outputs: Vec::new(),
+    }
+  }
+  pub fn symbolize(&mut self, addr: i64) -> Symbol {
+    let symbol = Symbol::Cell(addr);
+    self.memory.insert(addr, Expr::symbol(symbol));
+    symbol
+  }
+  pub fn push_input(&mut self, value: Expr) {
+    self.inputs.push_back(value);
+  }
+  pub fn cell(&self, addr: i64) -> Expr {
+    self
+      .memory
+      .get(&addr)
+      .cloned()
+      .unwrap_or_else(|| Expr::constant(0))
+  }
+  pub fn outputs(&self) -> &[Expr] {
+    &self.outputs
+  }
+  pub fn pc(&self) -> i64 {
+    self.pc
+  }
+  pub fn run(&mut self) -> Result<(), Error> {
+    for _ in 0..LIMIT {
+      if self.step()? {
+        debug!(pc = self.pc, outputs = self.outputs.len(), "symbolic halt");
+        return Ok(());
+      }
+    }
+    Err(Error::Limit(self.pc))
+  }
+  pub fn step(&mut self) -> Result<bool, Error> {
+    let pc = self.pc;
+    if self.concrete(pc).is_none() {
+      return Err(Error::Code(pc));
+    }
+    let fetch = |addr: i64| Some(self.concrete(addr).unwrap_or(0));
+    let (instruction, length) =
+      Instruction::decode(fetch, pc).map_err(|error| Error::Decode(pc, error))?;
+    trace!(pc, ?instruction, "symbolic step");
+    let mut next = pc + length;
+    match &instruction {
+      Instruction::Add((a, b, dest)) => {
+        let value = Expr::sum(self.source(a, 1)?, self.source(b, 2)?);
+        self.write(dest, 3, value)?;
+      }
+      Instruction::Multiply((a, b, dest)) => {
+        let value = Expr::product(self.source(a, 1)?, self.source(b, 2)?);
+        self.write(dest, 3, value)?;
+      }
+      Instruction::LessThan((a, b, dest)) => {
+        let value = Expr::less(self.source(a, 1)?, self.source(b, 2)?);
+        self.write(dest, 3, value)?;
+      }
+      Instruction::Equals((a, b, dest)) => {
+        let value = Expr::equals(self.source(a, 1)?, self.source(b, 2)?);
+        self.write(dest, 3, value)?;
+      }
+      Instruction::JumpIfTrue((cond, target)) | Instruction::JumpIfFalse((cond, target)) => {
+        let cond = self.source(cond, 1)?;
+        let taken = match cond.as_const() {
+          Some(value) => (value != 0) == matches!(instruction, Instruction::JumpIfTrue(_)),
+          None => return Err(Error::Branch(pc, cond)),
+        };
+        if taken {
+          next = self
+            .source(target, 2)?
+            .as_const()
+            .ok_or(Error::Address(pc))?;
+        }
+      }
+      Instruction::ReduceAbsoluteRelative(offset) => {
+        self.relative_base += self
+          .source(offset, 1)?
+          .as_const()
+          .ok_or(Error::Address(pc))?;
+      }
+      Instruction::Input(dest) => {
+        let value = match self.inputs.pop_front() {
+          Some(value) => value,
+          None => Expr::symbol(Symbol::Input(self.read)),
+        };
+        self.read += 1;
+        self.write(dest, 1, value)?;
+      }
+      Instruction::Output(src) => {
+        let value = self.source(src, 1)?;
+        self.outputs.push(value);
+      }
+      Instruction::Halt => return Ok(true),
+    }
+    self.pc = next;
+    Ok(false)
+  }
+
+  fn concrete(&self, addr: i64) -> Option<i64> {
+    match self.memory.get(&addr) {
+      Some(expr) => expr.as_const(),
+      None => Some(0),
+    }
+  }
+  fn read(&self, addr: i64) -> Result<Expr, Error> {
+    if addr < 0 {
+      return Err(Error::Decode(self.pc, error::Error::Address(addr)));
+    }
+    Ok(self.cell(addr))
+  }
+  fn source(&self, value: &Value, offset: i64) -> Result<Expr, Error> {
+    let operand = self.pc + offset;
+    if self.concrete(operand).is_none() {
+      let raw = self.cell(operand);
+      return Ok(match value {
+        Value::Direct(_) => raw,
+        Value::Stored(_) => Expr::load(raw),
+        Value::Relative(_) => Expr::load(Expr::sum(Expr::constant(self.relative_base), raw)),
+      });
+    }
+    match value {
+      Value::Direct(value) => Ok(Expr::constant(*value)),
+      Value::Stored(addr) => self.read(*addr),
+      Value::Relative(offset) => self.read(self.relative_base + offset),
+    }
+  }
+  fn write(&mut self, value: &Value, offset: i64, expr: Expr) -> Result<(), Error> {
+    if self.concrete(self.pc + offset).is_none() {
+      return Err(Error::Address(self.pc));
+    }
+    let addr = match value {
+      Value::Direct(addr) => *addr,
+      Value::Stored(addr) => self.concrete(*addr).ok_or(Error::Address(self.pc))?,
+      Value::Relative(offset) => self.relative_base + offset,
+    };
+    if addr < 0 {
+      return Err(Error::Decode(self.pc, error::Error::Address(addr)));
+    }
+    self.memory.insert(addr, expr);
+    Ok(())
+  }
+}
+
+pub fn solve(
+  expr: &Expr,
+  target: i64,
+  domains: &HashMap<Symbol, RangeInclusive<i64>>,
+) -> Result<Option<HashMap<Symbol, i64>>, Error> {
+  let poly = expr
+    .polynomial()
+    .ok_or(Error::NotPolynomial)?
+    .add(&Polynomial::constant(target.wrapping_neg()));
+  let domain = |symbol: Symbol| domains.get(&symbol).cloned().unwrap_or(i64::MIN..=i64::MAX);
+  let symbols: Vec<Symbol> = poly.symbols().into_iter().collect();
+  debug!(%poly, symbols = symbols.len(), "solving");
+  if symbols.is_empty() {
+    return Ok(if poly.as_const() == Some(0) {
+      Some(HashMap::new())
+    } else {
+      None
+    });
+  }
+  if poly.degree() == 1 && symbols.len() == 2 {
+    let (x, y) = (symbols[0], symbols[1]);
+    return Ok(linear(&poly, (x, domain(x)), (y, domain(y))));
+  }
+  let free = symbols
+    .iter()
+    .cloned()
+    .min_by_key(|symbol| {
+      let range = domain(*symbol);
+      let width = i128::from(*range.end()) - i128::from(*range.start());
+      (poly.degree_in(*symbol) != 1, std::cmp::Reverse(width))
+    })
+    .expect("at least one symbol");
+  let rest = symbols
+    .iter()
+    .filter(|symbol| **symbol != free)
+    .map(|symbol| match domains.get(symbol) {
+      Some(range) => Ok((*symbol, range.clone())),
+      None => Err(Error::Unbounded(*symbol)),
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  if rest.iter().any(|(_, range)| range.is_empty()) {
+    return Ok(None);
+  }
+  let mut values: HashMap<Symbol, i64> = rest
+    .iter()
+    .map(|(symbol, range)| (*symbol, *range.start()))
+    .collect();
+  loop {
+    if let Some(value) = root(&poly.substitute(&values), &domain(free)) {
+      values.insert(free, value);
+      return Ok(Some(values));
+    }
+    let mut idx = 0;
+    loop {
+      let (symbol, range) = match rest.get(idx) {
+        Some(entry) => entry,
+        None => return Ok(None),
+      };
+      let value = values[symbol];
+      if value < *range.end() {
+        values.insert(*symbol, value + 1);
+        break;
+      }
+      values.insert(*symbol, *range.start());
+      idx += 1;
+    }
+  }
+}
+
+fn root(poly: &Polynomial, domain: &RangeInclusive<i64>) -> Option<i64> {
+  let coefficients: BTreeMap<usize, i64> = poly
+    .terms
+    .iter()
+    .map(|(monomial, coefficient)| (monomial.len(), *coefficient))
+    .collect();
+  let lowest = match coefficients.keys().next() {
+    Some(lowest) => *lowest,
+    None if domain.is_empty() => return None,
+    None => return Some(*domain.start()),
+  };
+  if poly.degree() == 1 {
+    let (a, b) = (coefficients[&1], coefficients.get(&0).cloned().unwrap_or(0));
+    return match b.checked_rem(a) {
+      Some(0) => Some(b.wrapping_neg() / a).filter(|x| domain.contains(x)),
+      _ => None,
+    };
+  }
+  if lowest > 0 && domain.contains(&0) {
+    return Some(0);
+  }
+  let eval = |x: i64| {
+    coefficients.iter().fold(0i64, |acc, (power, coefficient)| {
+      acc.wrapping_add(coefficient.wrapping_mul(x.wrapping_pow(*power as u32)))
+    })
+  };
+  let constant = u128::from(coefficients[&lowest].unsigned_abs());
+  let bound = u128::from(
+    domain
+      .start()
+      .unsigned_abs()
+      .max(domain.end().unsigned_abs()),
+  );
+  let mut candidates = Vec::new();
+  let mut divisor: u128 = 1;
+  while divisor * divisor <= constant && divisor <= bound {
+    if constant % divisor == 0 {
+      candidates.push(divisor);
+      candidates.push(constant / divisor);
+    }
+    divisor += 1;
+  }
+  candidates
+    .into_iter()
+    .filter(|candidate| *candidate <= bound)
+    .flat_map(|candidate| vec![candidate as i64, (candidate as i64).wrapping_neg()])
+    .filter(|x| domain.contains(x) && eval(*x) == 0)
+    .min()
+}
+
+fn linear(
+  poly: &Polynomial,
+  (x, xs): (Symbol, RangeInclusive<i64>),
+  (y, ys): (Symbol, RangeInclusive<i64>),
+) -> Option<HashMap<Symbol, i64>> {
+  let a = i128::from(poly.coefficient(&[x]));
+  let b = i128::from(poly.coefficient(&[y]));
+  let c = -i128::from(poly.coefficient(&[]));
+  let (g, p, q) = extended_gcd(a, b);
+  if c % g != 0 {
+    return None;
+  }
+  let (x0, y0) = (p * (c / g), q * (c / g));
+  let (dx, dy) = (b / g, -a / g);
+  let (xlo, xhi) = steps(x0, dx, &xs);
+  let (ylo, yhi) = steps(y0, dy, &ys);
+  let k = xlo.max(ylo);
+  if k > xhi.min(yhi) {
+    return None;
+  }
+  let mut values = HashMap::new();
+  values.insert(x, (x0 + k * dx) as i64);
+  values.insert(y, (y0 + k * dy) as i64);
+  Some(values)
+}
+
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+  if b == 0 {
+    if a < 0 {
+      (-a, -1, 0)
+    } else {
+      (a, 1, 0)
+    }
+  } else {
+    let (g, p, q) = extended_gcd(b, a % b);
+    (g, q, p - (a / b) * q)
+  }
+}
+
+fn steps(start: i128, step: i128, range: &RangeInclusive<i64>) -> (i128, i128) {
+  let lo = i128::from(*range.start()) - start;
+  let hi = i128::from(*range.end()) - start;
+  if step > 0 {
+    (-floor_div(-lo, step), floor_div(hi, step))
+  } else {
+    (-floor_div(-hi, step), floor_div(lo, step))
+  }
+}
+
+fn floor_div(a: i128, b: i128) -> i128 {
+  let quotient = a / b;
+  if a % b != 0 && (a < 0) != (b < 0) {
+    quotient - 1
+  } else {
+    quotient
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn domains(ranges: &[(Symbol, RangeInclusive<i64>)]) -> HashMap<Symbol, RangeInclusive<i64>> {
+    ranges.iter().cloned().collect()
+  }
+
+  #[test]
+  fn polynomial_1() {
+    let (x, y) = (Symbol::Input(0), Symbol::Cell(2));
+    let expr = Expr::sum(
+      Expr::product(
+        Expr::symbol(x),
+        Expr::sum(Expr::symbol(y), Expr::constant(-3)),
+      ),
+      Expr::constant(7),
+    );
+    assert_eq!(expr.to_string(), "m2*in0 - 3*in0 + 7");
+    let values = [(x, 4), (y, 10)].iter().cloned().collect();
+    assert_eq!(expr.eval(&values), Some(35));
+    let opaque = Expr::less(expr.clone(), Expr::constant(0));
+    assert_eq!(opaque.polynomial(), None);
+    assert_eq!(opaque.eval(&values), Some(0));
+    assert_eq!(Expr::equals(expr.clone(), expr).as_const(), Some(1));
+  }
+  #[test]
+  fn execute_1() {
+    let mut executor = Executor::load(&[3, 13, 1002, 13, 3, 13, 1001, 13, 5, 13, 4, 13, 99, 0]);
+    executor.run().unwrap();
+    assert_eq!(executor.outputs().len(), 1);
+    assert_eq!(executor.outputs()[0].to_string(), "3*in0 + 5");
+    let solution = solve(&executor.outputs()[0], 47, &HashMap::new()).unwrap();
+    assert_eq!(solution.unwrap()[&Symbol::Input(0)], 14);
+    assert_eq!(solve(&executor.outputs()[0], 46, &HashMap::new()), Ok(None));
+  }
+  #[test]
+  fn execute_branch() {
+    let mut executor = Executor::load(&[3, 7, 1005, 7, 6, 99, 99, 0]);
+    assert_eq!(
+      executor.run(),
+      Err(Error::Branch(2, Expr::symbol(Symbol::Input(0))))
+    );
+    let mut executor = Executor::load(&[3, 7, 1005, 7, 6, 99, 99, 0]);
+    executor.push_input(Expr::constant(1));
+    assert_eq!(executor.run(), Ok(()));
+    assert_eq!(executor.pc(), 6);
+  }
+  #[test]
+  fn day02() {
+    let program = &helpers::loader::load_integer_row_list("../day/02/input.csv")[0];
+    let mut executor = Executor::load(program);
+    let noun = executor.symbolize(1);
+    let verb = executor.symbolize(2);
+    executor.run().unwrap();
+    let result = executor.cell(0);
+    let ranges = domains(&[(noun, 0..=99), (verb, 0..=99)]);
+    let solution = solve(&result, 19_690_720, &ranges).unwrap().unwrap();
+    assert_eq!((solution[&noun], solution[&verb]), (40, 19));
+    let solution = solve(&result, 6_627_023, &ranges).unwrap().unwrap();
+    assert_eq!((solution[&noun], solution[&verb]), (12, 2));
+  }
+  #[test]
+  fn solve_polynomial() {
+    let x = Symbol::Input(0);
+    let square = Expr::product(Expr::symbol(x), Expr::symbol(x));
+    let solution = solve(&square, 49, &domains(&[(x, 0..=100)])).unwrap();
+    assert_eq!(solution.unwrap()[&x], 7);
+    let solution = solve(&square, 49, &domains(&[(x, -100..=0)])).unwrap();
+    assert_eq!(solution.unwrap()[&x], -7);
+    assert_eq!(solve(&square, 50, &HashMap::new()), Ok(None));
+
+    let y = Symbol::Input(1);
+    let product = Expr::product(Expr::symbol(x), Expr::symbol(y));
+    let solution = solve(&product, 391, &domains(&[(x, 2..=100), (y, 2..=100)]))
+      .unwrap()
+      .unwrap();
+    assert_eq!(solution[&x] * solution[&y], 391);
+    let solution = solve(&product, 391, &domains(&[(x, 20..=100)]))
+      .unwrap()
+      .unwrap();
+    assert_eq!((solution[&x], solution[&y]), (23, 17));
+    let cubic = Expr::product(product, Expr::symbol(y));
+    assert_eq!(
+      solve(&cubic, 391, &HashMap::new()),
+      Err(Error::Unbounded(y))
+    );
+  }
+  #[test]
+  fn solve_linear() {
+    let (x, y) = (Symbol::Cell(1), Symbol::Cell(2));
+    let expr = Expr::sum(
+      Expr::product(Expr::constant(3), Expr::symbol(x)),
+      Expr::product(Expr::constant(5), Expr::symbol(y)),
+    );
+    let ranges = domains(&[(x, 1_000_000..=i64::MAX), (y, 0..=i64::MAX)]);
+    let solution = solve(&expr, 1_000_000_007, &ranges).unwrap().unwrap();
+    assert_eq!(expr.eval(&solution), Some(1_000_000_007));
+    assert!(solution[&x] >= 1_000_000 && solution[&y] >= 0);
+    let even = Expr::product(
+      Expr::constant(2),
+      Expr::sum(Expr::symbol(x), Expr::symbol(y)),
+    );
+    assert_eq!(solve(&even, 7, &HashMap::new()), Ok(None));
+  }
+}
@@ -0,0 +1,324 @@
+use super::error;
+use super::event::Event;
+use super::Computer;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+const SLICE: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+  Faulted(i64, error::Error),
+  InputClosed(i64),
+  OutputClosed(i64),
+}
+impl std::fmt::Display for Error {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Error::Faulted(pc, error) => write!(fmt, "Faulted at {}: {}", pc, error),
+      Error::InputClosed(pc) => write!(fmt, "Input closed while waiting at {}.", pc),
+      Error::OutputClosed(pc) => write!(fmt, "Output closed while sending from {}.", pc),
+    }
+  }
+}
+
+pub trait Input {
+  fn poll_input(&mut self, cx: &mut Context) -> Poll<Option<i64>>;
+}
+pub trait Output {
+  fn poll_output(&mut self, cx: &mut Context, value: i64) -> Poll<bool>;
+}
+
+impl<T: Input + ?Sized> Input for &mut T {
+  fn poll_input(&mut self, cx: &mut Context) -> Poll<Option<i64>> {
+    (**self).poll_input(cx)
+  }
+}
+impl<T: Output + ?Sized> Output for &mut T {
+  fn poll_output(&mut self, cx: &mut Context, value: i64) -> Poll<bool> {
+    (**self).poll_output(cx, value)
+  }
+}
+
+impl Input for VecDeque<i64> {
+  fn poll_input(&mut self, _cx: &mut Context) -> Poll<Option<i64>> {
+    Poll::Ready(self.pop_front())
+  }
+}
+impl Output for Vec<i64> {
+  fn poll_output(&mut self, _cx: &mut Context, value: i64) -> Poll<bool> {
+    self.push(value);
+    Poll::Ready(true)
+  }
+}
+
+struct Shared {
+  queue: VecDeque<i64>,
+  waker: Option<Waker>,
+  senders: usize,
+  receiving: bool,
+}
+
+pub struct Sender {
+  shared: Rc<RefCell<Shared>>,
+}
+pub struct Receiver {
+  shared: Rc<RefCell<Shared>>,
+}
+
+pub fn channel() -> (Sender, Receiver) {
+  let shared = Rc::new(RefCell::new(Shared {
+    queue: VecDeque::new(),
+    waker: None,
+    senders: 1,
+    receiving: true,
+  }));
+  (
+    Sender {
+      shared: shared.clone(),
+    },
+    Receiver { shared },
+  )
+}
+
+impl Sender {
+  pub fn send(&self, value: i64) -> bool {
+    let mut shared = self.shared.borrow_mut();
+    if !shared.receiving {
+      return false;
+    }
+    shared.queue.push_back(value);
+    if let Some(waker) = shared.waker.take() {
+      waker.wake();
+    }
+    true
+  }
+}
+impl Clone for Sender {
+  fn clone(&self) -> Sender {
+    self.shared.borrow_mut().senders += 1;
+    Sender {
+      shared: self.shared.clone(),
+    }
+  }
+}
+impl Drop for Sender {
+  fn drop(&mut self) {
+    let mut shared = self.shared.borrow_mut();
+    shared.senders -= 1;
+    if shared.senders == 0 {
+      if let Some(waker) = shared.waker.take() {
+        waker.wake();
+      }
+    }
+  }
+}
+impl Output for Sender {
+  fn poll_output(&mut self, _cx: &mut Context, value: i64) -> Poll<bool> {
+    Poll::Ready(self.send(value))
+  }
+}
+
+impl Receiver {
+  pub fn try_recv(&self) -> Option<i64> {
+    self.shared.borrow_mut().queue.pop_front()
+  }
+  pub fn drain(&self) -> Vec<i64> {
+    self.shared.borrow_mut().queue.drain(..).collect()
+  }
+}
+impl Drop for Receiver {
+  fn drop(&mut self) {
+    self.shared.borrow_mut().receiving = false;
+  }
+}
+impl Input for Receiver {
+  fn poll_input(&mut self, cx: &mut Context) -> Poll<Option<i64>> {
+    let mut shared = self.shared.borrow_mut();
+    match shared.queue.pop_front() {
+      Some(value) => Poll::Ready(Some(value)),
+      None if shared.senders == 0 => Poll::Ready(None),
+      None => {
+        shared.waker = Some(cx.waker().clone());
+        Poll::Pending
+      }
+    }
+  }
+}
+
+pub struct Drive<'a, I, O> {
+  computer: &'a mut Computer,
+  input: I,
+  output: O,
+  pending: Option<(i64, i64)>,
+}
+
+impl Computer {
+  pub fn drive<I: Input, O: Output>(&mut self, input: I, output: O) -> Drive<'_, I, O> {
+    Drive {
+      computer: self,
+      input,
+      output,
+      pending: None,
+    }
+  }
+}
+
+impl<'a, I: Input + Unpin, O: Output + Unpin> Future for Drive<'a, I, O> {
+  type Output = Result<(), Error>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+    let drive = self.get_mut();
+    loop {
+      if let Some((pc, value)) = drive.pending {
+        match drive.output.poll_output(cx, value) {
+          Poll::Ready(true) => drive.pending = None,
+          Poll::Ready(false) => return Poll::Ready(Err(Error::OutputClosed(pc))),
+          Poll::Pending => return Poll::Pending,
+        }
+      }
+      match drive.computer.resume_for(SLICE) {
+        Event::Output { pc, value } => drive.pending = Some((pc, value)),
+        Event::Input { pc, .. } => match drive.input.poll_input(cx) {
+          Poll::Ready(Some(value)) => {
+            drive.computer.input_value(value);
+          }
+          Poll::Ready(None) => return Poll::Ready(Err(Error::InputClosed(pc))),
+          Poll::Pending => return Poll::Pending,
+        },
+        Event::Halted { .. } => return Poll::Ready(Ok(())),
+        Event::Faulted { pc, error } => return Poll::Ready(Err(Error::Faulted(pc, error))),
+        Event::Breakpoint { .. } => (),
+        Event::BudgetExhausted { .. } => {
+          cx.waker().wake_by_ref();
+          return Poll::Pending;
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::atomic::{AtomicBool, Ordering};
+  use std::sync::Arc;
+  use std::task::Wake;
+
+  struct Flag(AtomicBool);
+  impl Wake for Flag {
+    fn wake(self: Arc<Self>) {
+      self.0.store(true, Ordering::SeqCst);
+    }
+  }
+
+  type Task<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+  struct Executor<'a> {
+    tasks: Vec<(Arc<Flag>, Task<'a>)>,
+  }
+
+  impl<'a> Executor<'a> {
+    fn new() -> Executor<'a> {
+      Executor { tasks: Vec::new() }
+    }
+    fn spawn<F: Future<Output = ()> + 'a>(&mut self, task: F) {
+      self
+        .tasks
+        .push((Arc::new(Flag(AtomicBool::new(true))), Box::pin(task)));
+    }
+    fn run(&mut self) -> usize {
+      let mut polls = 0;
+      while self.tasks.iter().any(|(flag, _)| flag.0.load(Ordering::SeqCst)) {
+        let mut idx = 0;
+        while idx < self.tasks.len() {
+          let (flag, task) = &mut self.tasks[idx];
+          if flag.0.swap(false, Ordering::SeqCst) {
+            polls += 1;
+            let waker = Waker::from(flag.clone());
+            if task.as_mut().poll(&mut Context::from_waker(&waker)).is_ready() {
+              drop(self.tasks.remove(idx));
+              continue;
+            }
+          }
+          idx += 1;
+        }
+      }
+      polls
+    }
+  }
+
+  #[test]
+  fn drive_1() {
+    let mut computer = Computer::load(0, &[3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+    let mut output = Vec::new();
+    let mut executor = Executor::new();
+    executor.spawn(async {
+      let input: VecDeque<i64> = vec![14].into();
+      assert_eq!(computer.drive(input, &mut output).await, Ok(()));
+    });
+    executor.run();
+    drop(executor);
+    assert_eq!(output, vec![42]);
+  }
+  #[test]
+  fn drive_input_closed() {
+    let mut computer = Computer::load(0, &[3, 0, 99]);
+    let mut executor = Executor::new();
+    executor.spawn(async {
+      let (sender, receiver) = channel();
+      drop(sender);
+      assert_eq!(
+        computer.drive(receiver, Vec::new()).await,
+        Err(Error::InputClosed(0))
+      );
+    });
+    executor.run();
+  }
+  #[test]
+  fn drive_feedback() {
+    let program = vec![
+      3, 52, 1001, 52, -5, 52, 3, 53, 1, 52, 56, 54, 1007, 54, 5, 55, 1005, 55, 26, 1001, 54, -5,
+      54, 1105, 1, 12, 1, 53, 54, 53, 1008, 54, 0, 55, 1001, 55, 1, 55, 2, 53, 55, 53, 4, 53, 1001,
+      56, -1, 56, 1005, 56, 6, 99, 0, 0, 0, 0, 10,
+    ];
+    let phases = [9, 7, 8, 5, 6];
+    let (senders, receivers): (Vec<Sender>, Vec<Receiver>) =
+      phases.iter().map(|_| channel()).unzip();
+    phases
+      .iter()
+      .zip(senders.iter())
+      .for_each(|(phase, sender)| assert!(sender.send(*phase)));
+    senders[0].send(0);
+    let (result, last) = channel();
+    let mut executor = Executor::new();
+    for (idx, receiver) in receivers.into_iter().enumerate() {
+      let sender = senders[(idx + 1) % phases.len()].clone();
+      let result = result.clone();
+      let mut computer = Computer::load(idx, &program);
+      executor.spawn(async move {
+        let tap = Tap { sender, result };
+        let _ = computer.drive(receiver, tap).await;
+      });
+    }
+    drop(senders);
+    drop(result);
+    assert!(executor.run() > phases.len());
+    assert_eq!(last.drain().pop(), Some(18_216));
+  }
+
+  struct Tap {
+    sender: Sender,
+    result: Sender,
+  }
+  impl Output for Tap {
+    fn poll_output(&mut self, _cx: &mut Context, value: i64) -> Poll<bool> {
+      self.result.send(value);
+      Poll::Ready(self.sender.send(value))
+    }
+  }
+}
@@ -0,0 +1,380 @@
+use super::error::Error;
+use super::flow::*;
+use super::history::History;
+use super::instruction::Instruction;
+use super::memory::Value;
+use super::state::State;
+use super::Computer;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use tracing::debug;
+
+pub fn translate(name: &str, program: &[i64]) -> String {
+  let blocks = blocks(program);
+  let code = code_ranges(&blocks);
+
+  let mut out = String::new();
+  writeln!(out, "// Translated by icc::translate, do not edit.").unwrap();
+  writeln!(
+    out,
+    "#[allow(unused_parens, unreachable_code, clippy::all)]"
+  )
+  .unwrap();
+  writeln!(out, "pub mod {} {{", name).unwrap();
+  writeln!(out, "  use icc::state::State;").unwrap();
+  writeln!(out, "  use icc::translate::Frame;").unwrap();
+  writeln!(
+    out,
+    "  pub const PROGRAM: [i64; {}] = {:?};",
+    program.len(),
+    program
+  )
+  .unwrap();
+  writeln!(
+    out,
+    "  const CODE: [(i64, i64); {}] = {:?};",
+    code.len(),
+    code
+  )
+  .unwrap();
+  writeln!(out, "  pub fn load(id: usize) -> Frame {{").unwrap();
+  writeln!(out, "    Frame::load(id, &PROGRAM, &CODE)").unwrap();
+  writeln!(out, "  }}").unwrap();
+  writeln!(out, "  pub fn run(frame: &mut Frame) -> State {{").unwrap();
+  writeln!(out, "    frame.run(exec)").unwrap();
+  writeln!(out, "  }}").unwrap();
+  writeln!(
+    out,
+    "  pub fn run_to_end(frame: &mut Frame, input: &[i64]) -> Result<Vec<i64>, String> {{"
+  )
+  .unwrap();
+  writeln!(out, "    frame.run_to_end(exec, input)").unwrap();
+  writeln!(out, "  }}").unwrap();
+  writeln!(out, "  fn exec(f: &mut Frame) -> Option<State> {{").unwrap();
+  writeln!(out, "    loop {{").unwrap();
+  writeln!(out, "      match f.pc() {{").unwrap();
+  blocks.iter().for_each(|block| emit_block(&mut out, block));
+  writeln!(out, "        _ => return Some(f.fallback()),").unwrap();
+  writeln!(out, "      }}").unwrap();
+  writeln!(out, "    }}").unwrap();
+  writeln!(out, "  }}").unwrap();
+  writeln!(out, "}}").unwrap();
+  out
+}
+
+pub fn translate_to_file<P: AsRef<Path>>(
+  name: &str,
+  program: &[i64],
+  path: P,
+) -> std::io::Result<()> {
+  std::fs::write(path, translate(name, program))
+}
+
+fn emit_block(out: &mut String, block: &Block) {
+  writeln!(out, "        {} => {{", block.leader).unwrap();
+  for (at, instruction) in block.body.iter() {
+    emit_instruction(out, *at, instruction);
+  }
+  match block.exit {
+    Exit::Fallthrough(next) => {
+      if !matches!(
+        block.body.last(),
+        Some((_, Instruction::Halt))
+          | Some((_, Instruction::Input(_)))
+          | Some((_, Instruction::Output(_)))
+      ) {
+        writeln!(out, "          f.set_pc({});", next).unwrap();
+      }
+    }
+    Exit::Stop(at) => {
+      writeln!(out, "          f.set_pc({});", at).unwrap();
+      writeln!(out, "          return Some(f.fallback());").unwrap();
+    }
+  }
+  writeln!(out, "        }}").unwrap();
+}
+
+fn source(value: &Value) -> String {
+  match value {
+    Value::Direct(val) => format!("({})", val),
+    Value::Stored(addr) => format!("f.read({})?", addr),
+    Value::Relative(base) => format!("f.read(f.relative_base() + ({}))?", base),
+  }
+}
+
+fn destination(value: &Value) -> String {
+  match value {
+    Value::Direct(addr) => format!("{}", addr),
+    Value::Stored(addr) => format!("f.read({})?", addr),
+    Value::Relative(base) => format!("f.relative_base() + ({})", base),
+  }
+}
+
+fn emit_write(out: &mut String, dest: &Value, value: &str, next: i64) {
+  writeln!(out, "          let v = {};", value).unwrap();
+  writeln!(out, "          let d = {};", destination(dest)).unwrap();
+  writeln!(out, "          if f.write(d, v) {{").unwrap();
+  writeln!(out, "            f.set_pc({});", next).unwrap();
+  writeln!(out, "            return Some(f.fallback());").unwrap();
+  writeln!(out, "          }}").unwrap();
+}
+
+fn emit_instruction(out: &mut String, at: i64, instruction: &Instruction) {
+  let next = at + instruction.length();
+  match instruction {
+    Instruction::Add((a, b, dest)) => {
+      emit_write(out, dest, &format!("{} + {}", source(a), source(b)), next)
+    }
+    Instruction::Multiply((a, b, dest)) => {
+      emit_write(out, dest, &format!("{} * {}", source(a), source(b)), next)
+    }
+    Instruction::LessThan((a, b, dest)) => emit_write(
+      out,
+      dest,
+      &format!("if {} < {} {{ 1 }} else {{ 0 }}", source(a), source(b)),
+      next,
+    ),
+    Instruction::Equals((a, b, dest)) => emit_write(
+      out,
+      dest,
+      &format!("if {} == {} {{ 1 }} else {{ 0 }}", source(a), source(b)),
+      next,
+    ),
+    Instruction::JumpIfTrue((value, dest)) => {
+      writeln!(out, "          if {} != 0 {{", source(value)).unwrap();
+      writeln!(out, "            let d = {};", source(dest)).unwrap();
+      writeln!(out, "            f.set_pc(d);").unwrap();
+      writeln!(out, "            continue;").unwrap();
+      writeln!(out, "          }}").unwrap();
+    }
+    Instruction::JumpIfFalse((value, dest)) => {
+      writeln!(out, "          if {} == 0 {{", source(value)).unwrap();
+      writeln!(out, "            let d = {};", source(dest)).unwrap();
+      writeln!(out, "            f.set_pc(d);").unwrap();
+      writeln!(out, "            continue;").unwrap();
+      writeln!(out, "          }}").unwrap();
+    }
+    Instruction::ReduceAbsoluteRelative(value) => {
+      writeln!(out, "          let v = {};", source(value)).unwrap();
+      writeln!(out, "          f.adjust_relative_base(v);").unwrap();
+    }
+    Instruction::Input(dest) => {
+      writeln!(out, "          let d = {};", destination(dest)).unwrap();
+      writeln!(out, "          f.set_pc({});", next).unwrap();
+      writeln!(out, "          return Some(f.wait_input(d));").unwrap();
+    }
+    Instruction::Output(value) => {
+      writeln!(out, "          let v = {};", source(value)).unwrap();
+      writeln!(out, "          f.set_pc({});", next).unwrap();
+      writeln!(out, "          return Some(f.output(v));").unwrap();
+    }
+    Instruction::Halt => {
+      writeln!(out, "          f.set_pc({});", at).unwrap();
+      writeln!(out, "          return Some(State::Halted);").unwrap();
+    }
+  }
+}
+
+pub struct Frame {
+  id: usize,
+  error: Option<Error>,
+  input: Option<i64>,
+  memory: HashMap<i64, i64>,
+  code: Vec<(i64, i64)>,
+  image: i64,
+  state: State,
+  pc: i64,
+  relative_base: i64,
+  interpreter: Option<Computer>,
+}
+
+impl Frame {
+  pub fn load(id: usize, program: &[i64], code: &[(i64, i64)]) -> Frame {
+    Frame {
+      id,
+      error: None,
+      input: None,
+      memory: program
+        .iter()
+        .enumerate()
+        .map(|(idx, val)| (idx as i64, *val))
+        .collect(),
+      code: code.to_vec(),
+      image: program.len() as i64,
+      state: State::Ready,
+      pc: 0,
+      relative_base: 0,
+      interpreter: None,
+    }
+  }
+
+  pub fn run(&mut self, exec: fn(&mut Frame) -> Option<State>) -> State {
+    if let Some(computer) = self.interpreter.as_mut() {
+      return computer.run();
+    }
+    if self.state == State::Input {
+      return State::Input;
+    }
+    self.state = match exec(self) {
+      Some(state) => state,
+      None => State::Crashed,
+    };
+    self.state.clone()
+  }
+
+  pub fn run_to_end(
+    &mut self,
+    exec: fn(&mut Frame) -> Option<State>,
+    input: &[i64],
+  ) -> Result<Vec<i64>, String> {
+    let mut input: Vec<i64> = input.iter().cloned().rev().collect();
+    let mut output = Vec::new();
+    loop {
+      match self.run(exec) {
+        State::Crashed => return Err(self.error()),
+        State::Halted => break,
+        State::Ready => (),
+        State::Running => (),
+        State::Output(_id, value) => output.push(value),
+        State::Input => {
+          if !self.input_value(input.pop().expect("Asked for more input than provided.")) {
+            panic!("Asked for but refused input")
+          }
+        }
+      }
+    }
+    Ok(output)
+  }
+
+  pub fn input_value(&mut self, value: i64) -> bool {
+    if let Some(computer) = self.interpreter.as_mut() {
+      return computer.input_value(value);
+    }
+    if let Some(dest) = self.input.take() {
+      self.state = State::Running;
+      if self.write(dest, value) {
+        self.fallback_to(State::Running);
+      }
+      return true;
+    }
+    false
+  }
+  pub fn want_input(&self) -> Option<usize> {
+    match self.interpreter.as_ref() {
+      Some(computer) => computer.want_input(),
+      None => self.input.map(|_| self.id),
+    }
+  }
+  pub fn error(&self) -> String {
+    match self.interpreter.as_ref() {
+      Some(computer) => computer.error(),
+      None => match self.error.clone() {
+        Some(error) => format!("Error: {}", error),
+        None => "I'm fully operational, and all my circuits are functioning perfectly.".to_owned(),
+      },
+    }
+  }
+  pub fn read_memory(&self, addr: i64) -> Option<i64> {
+    match self.interpreter.as_ref() {
+      Some(computer) => computer.read_memory(addr),
+      None => self.memory.get(&addr).cloned(),
+    }
+  }
+  pub fn edit_memory(&mut self, addr: i64, value: i64) {
+    if let Some(computer) = self.interpreter.as_mut() {
+      computer.edit_memory(addr, value);
+    } else if self.write(addr, value) {
+      self.fallback_to(self.state.clone());
+    }
+  }
+  pub fn is_interpreted(&self) -> bool {
+    self.interpreter.is_some()
+  }
+
+  pub fn pc(&self) -> i64 {
+    self.pc
+  }
+  pub fn set_pc(&mut self, pc: i64) {
+    self.pc = pc;
+  }
+  pub fn relative_base(&self) -> i64 {
+    self.relative_base
+  }
+  pub fn adjust_relative_base(&mut self, value: i64) {
+    self.relative_base += value;
+  }
+  pub fn read(&mut self, addr: i64) -> Option<i64> {
+    if addr < 0 {
+      self.error = Some(Error::Address(addr));
+      return None;
+    }
+    Some(self.memory.get(&addr).cloned().unwrap_or(0))
+  }
+  pub fn write(&mut self, addr: i64, value: i64) -> bool {
+    let old = self.memory.insert(addr, value);
+    old != Some(value) && self.is_code(addr)
+  }
+  pub fn wait_input(&mut self, dest: i64) -> State {
+    self.input = Some(dest);
+    State::Input
+  }
+  pub fn output(&self, value: i64) -> State {
+    State::Output(self.id, value)
+  }
+  pub fn fallback(&mut self) -> State {
+    self.fallback_to(State::Running);
+    self.interpreter.as_mut().unwrap().run()
+  }
+
+  fn is_code(&self, addr: i64) -> bool {
+    self
+      .code
+      .iter()
+      .any(|(from, to)| *from <= addr && addr < *to)
+  }
+  fn fallback_to(&mut self, state: State) {
+    debug!(
+      machine = self.id,
+      pc = self.pc,
+      "falling back to the interpreter"
+    );
+    self.interpreter = Some(Computer {
+      id: self.id,
+      error: self.error.take(),
+      input: None,
+      memory: std::mem::take(&mut self.memory),
+      state,
+      pc: self.pc,
+      relative_base: self.relative_base,
+      image: self.image,
+      breakpoints: Default::default(),
+      decoded: Default::default(),
+      pure: Vec::new(),
+      history: History::new(0),
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn emits_module() {
+    let source = translate("tiny", &[104, 7, 99]);
+    assert!(source.contains("pub mod tiny {"));
+    assert!(source.contains("return Some(f.output(v));"));
+    assert!(source.contains("return Some(State::Halted);"));
+  }
+  #[test]
+  fn frame_fallback() {
+    fn exec(f: &mut Frame) -> Option<State> {
+      Some(f.fallback())
+    }
+    let program = [3, 0, 4, 0, 99];
+    let mut frame = Frame::load(0, &program, &[(0, 5)]);
+    assert_eq!(frame.run_to_end(exec, &[42]), Ok(vec![42]));
+    assert!(frame.is_interpreted());
+  }
+}
@@ -0,0 +1,482 @@
+use super::event::Event;
+use super::instruction::Instruction;
+use super::memory::Value;
+use super::Computer;
+use crossterm::event::{self, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, queue, style, terminal};
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_millis(50);
+const TRAIL: usize = 64;
+const OUTPUTS: usize = 256;
+const MAX_SPEED: usize = 1 << 20;
+const SHADES: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+  Ready,
+  Waiting(i64),
+  Halted,
+  Faulted(i64, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+  Char(char),
+  Enter,
+  Backspace,
+  Escape,
+}
+
+pub struct Viewer {
+  computer: Computer,
+  status: Status,
+  running: bool,
+  speed: usize,
+  steps: usize,
+  trail: VecDeque<i64>,
+  heat: HashMap<i64, u64>,
+  outputs: VecDeque<i64>,
+  inputs: VecDeque<i64>,
+  prompt: String,
+}
+
+impl Viewer {
+  pub fn new(computer: Computer) -> Viewer {
+    Viewer {
+      computer,
+      status: Status::Ready,
+      running: false,
+      speed: 1,
+      steps: 0,
+      trail: VecDeque::new(),
+      heat: HashMap::new(),
+      outputs: VecDeque::new(),
+      inputs: VecDeque::new(),
+      prompt: String::new(),
+    }
+  }
+  pub fn with_input(mut self, input: &[i64]) -> Viewer {
+    self.inputs.extend(input);
+    self
+  }
+  pub fn with_speed(mut self, speed: usize) -> Viewer {
+    self.speed = speed.clamp(1, MAX_SPEED);
+    self
+  }
+  pub fn computer(&self) -> &Computer {
+    &self.computer
+  }
+  pub fn status(&self) -> &Status {
+    &self.status
+  }
+  pub fn outputs(&self) -> impl Iterator<Item = &i64> {
+    self.outputs.iter()
+  }
+
+  pub fn step(&mut self) -> bool {
+    match self.status {
+      Status::Halted | Status::Faulted(..) => return false,
+      Status::Waiting(_) => match self.inputs.pop_front() {
+        Some(value) => self.feed(value),
+        None => return false,
+      },
+      Status::Ready => (),
+    }
+    let pc = self.computer.pc();
+    self.touch(pc);
+    let event = self.computer.resume_for(1);
+    self.steps += 1;
+    self.trail.push_back(pc);
+    if self.trail.len() > TRAIL {
+      self.trail.pop_front();
+    }
+    match event {
+      Event::Output { value, .. } => {
+        self.outputs.push_back(value);
+        if self.outputs.len() > OUTPUTS {
+          self.outputs.pop_front();
+        }
+      }
+      Event::Input { pc, .. } => match self.inputs.pop_front() {
+        Some(value) => self.feed(value),
+        None => {
+          self.status = Status::Waiting(pc);
+          return false;
+        }
+      },
+      Event::Halted { .. } => {
+        self.status = Status::Halted;
+        return false;
+      }
+      Event::Faulted { pc, error } => {
+        self.status = Status::Faulted(pc, error.to_string());
+        return false;
+      }
+      Event::Breakpoint { .. } | Event::BudgetExhausted { .. } => (),
+    }
+    true
+  }
+  pub fn tick(&mut self) {
+    if !self.running {
+      return;
+    }
+    for _ in 0..self.speed {
+      if !self.step() {
+        break;
+      }
+    }
+  }
+  pub fn handle(&mut self, key: Key) -> bool {
+    match key {
+      Key::Escape | Key::Char('q') => return false,
+      Key::Char(' ') => self.running = !self.running,
+      Key::Char('s') => {
+        self.running = false;
+        self.step();
+      }
+      Key::Char('>') => self.speed = (self.speed * 2).min(MAX_SPEED),
+      Key::Char('<') => self.speed = (self.speed / 2).max(1),
+      Key::Char(c) if c.is_ascii_digit() || c == '-' => self.prompt.push(c),
+      Key::Backspace => {
+        self.prompt.pop();
+      }
+      Key::Enter => {
+        if let Ok(value) = self.prompt.parse() {
+          self.inputs.push_back(value);
+        }
+        self.prompt.clear();
+      }
+      Key::Char(_) => (),
+    }
+    true
+  }
+
+  pub fn render(&self, width: usize, height: usize) -> Vec<String> {
+    let body = height.saturating_sub(4);
+    let left = (width / 2).min(40);
+    let right = width.saturating_sub(left + 3);
+    let listing = self.listing(body);
+    let heatmap = self.heatmap(right, body);
+    let mut lines = vec![self.header()];
+    for row in 0..body {
+      lines.push(format!(
+        "{:left$} | {}",
+        listing.get(row).map(String::as_str).unwrap_or(""),
+        heatmap.get(row).map(String::as_str).unwrap_or(""),
+        left = left
+      ));
+    }
+    let outputs: Vec<String> = self.outputs.iter().map(i64::to_string).collect();
+    let mut shown = outputs.join(" ");
+    let room = width.saturating_sub(5);
+    if shown.len() > room {
+      shown = shown[shown.len() - room..].to_owned();
+    }
+    lines.push(format!("out: {}", shown));
+    let queued: Vec<String> = self.inputs.iter().map(i64::to_string).collect();
+    lines.push(format!("in [{}] > {}_", queued.join(" "), self.prompt));
+    lines.push("space run/pause  s step  < > speed  digits+enter input  q quit".to_owned());
+    lines
+      .into_iter()
+      .map(|line| {
+        let line: String = line.chars().take(width).collect();
+        format!("{:width$}", line, width = width)
+      })
+      .collect()
+  }
+
+  pub fn run(&mut self) -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = self.event_loop(&mut out);
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+  }
+
+  fn event_loop(&mut self, out: &mut io::Stdout) -> io::Result<()> {
+    loop {
+      let (width, height) = terminal::size()?;
+      for (row, line) in self
+        .render(width as usize, height as usize)
+        .iter()
+        .enumerate()
+      {
+        queue!(out, cursor::MoveTo(0, row as u16), style::Print(line))?;
+      }
+      out.flush()?;
+      if event::poll(TICK)? {
+        if let event::Event::Key(key) = event::read()? {
+          if key.kind != KeyEventKind::Press {
+            continue;
+          }
+          let key = match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Key::Escape,
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Esc => Key::Escape,
+            _ => continue,
+          };
+          if !self.handle(key) {
+            return Ok(());
+          }
+        }
+      }
+      self.tick();
+    }
+  }
+
+  fn feed(&mut self, value: i64) {
+    self.computer.input_value(value);
+    self.status = Status::Ready;
+  }
+  fn decode(&self, pc: i64) -> Option<(Instruction, i64)> {
+    Instruction::decode(|addr| self.computer.read_memory(addr), pc).ok()
+  }
+  fn touch(&mut self, pc: i64) {
+    let (instruction, length) = match self.decode(pc) {
+      Some(decoded) => decoded,
+      None => return,
+    };
+    let base = self.computer.relative_base();
+    let mut cells: Vec<i64> = (pc..pc + length).collect();
+    let (sources, dest) = operands(&instruction);
+    for source in sources {
+      match source {
+        Value::Stored(addr) => cells.push(*addr),
+        Value::Relative(offset) => cells.push(base + offset),
+        Value::Direct(_) => (),
+      }
+    }
+    match dest {
+      Some(Value::Direct(addr)) => cells.push(*addr),
+      Some(Value::Relative(offset)) => cells.push(base + offset),
+      Some(Value::Stored(addr)) => cells.extend(self.computer.read_memory(*addr)),
+      None => (),
+    }
+    for cell in cells {
+      *self.heat.entry(cell).or_insert(0) += 1;
+    }
+  }
+
+  fn header(&self) -> String {
+    let status = match &self.status {
+      Status::Ready if self.running => "running".to_owned(),
+      Status::Ready => "paused".to_owned(),
+      Status::Waiting(pc) => format!("waiting for input at {}", pc),
+      Status::Halted => "halted".to_owned(),
+      Status::Faulted(pc, error) => format!("faulted at {}: {}", pc, error),
+    };
+    format!(
+      "pc {}  rb {}  steps {}  speed {}/tick  {}",
+      self.computer.pc(),
+      self.computer.relative_base(),
+      self.steps,
+      self.speed,
+      status
+    )
+  }
+
+  fn listing(&self, rows: usize) -> Vec<String> {
+    let pc = self.computer.pc();
+    let mut before: Vec<i64> = Vec::new();
+    for addr in self.trail.iter().rev() {
+      if before.len() >= rows / 3 {
+        break;
+      }
+      if *addr < pc && !before.contains(addr) {
+        before.push(*addr);
+      }
+    }
+    before.sort_unstable();
+    let mut lines: Vec<String> = before
+      .into_iter()
+      .map(|addr| self.line(addr, false).0)
+      .collect();
+    let mut addr = pc;
+    while lines.len() < rows {
+      let (line, length) = self.line(addr, addr == pc);
+      lines.push(line);
+      addr += length;
+    }
+    lines
+  }
+  fn line(&self, addr: i64, current: bool) -> (String, i64) {
+    let marker = if current { '>' } else { ' ' };
+    match self.decode(addr) {
+      Some((instruction, length)) => (
+        format!("{}{:>6}  {}", marker, addr, disassemble(&instruction)),
+        length,
+      ),
+      None => {
+        let value = match self.computer.read_memory(addr) {
+          Some(value) => value.to_string(),
+          None => "?".to_owned(),
+        };
+        (format!("{}{:>6}  data {}", marker, addr, value), 1)
+      }
+    }
+  }
+
+  fn heatmap(&self, width: usize, rows: usize) -> Vec<String> {
+    if width == 0 || rows < 2 {
+      return Vec::new();
+    }
+    let touched = self.heat.keys().max().cloned().unwrap_or(0);
+    let span = (self.computer.snapshot().image().max(touched + 1)).max(1) as usize;
+    let chars = width * (rows - 1);
+    let per = span.div_ceil(chars).max(1);
+    let mut buckets = vec![0u64; span.div_ceil(per)];
+    for (addr, heat) in &self.heat {
+      if *addr >= 0 {
+        buckets[*addr as usize / per] += heat;
+      }
+    }
+    let bits = |value: u64| u64::from(64 - value.leading_zeros());
+    let top = bits(buckets.iter().cloned().max().unwrap_or(0)).max(1);
+    let levels = SHADES.len() as u64 - 1;
+    let shade = |heat: u64| match heat {
+      0 => SHADES[0],
+      heat => SHADES[(1 + (bits(heat) - 1) * (levels - 1) / top.max(1)) as usize],
+    };
+    let mut lines = vec![format!("memory 0..{}, {} cells per char", span, per)];
+    for chunk in buckets.chunks(width) {
+      lines.push(chunk.iter().map(|heat| shade(*heat)).collect());
+    }
+    lines
+  }
+}
+
+fn operands(instruction: &Instruction) -> (Vec<&Value>, Option<&Value>) {
+  match instruction {
+    Instruction::Add((a, b, d))
+    | Instruction::Multiply((a, b, d))
+    | Instruction::LessThan((a, b, d))
+    | Instruction::Equals((a, b, d)) => (vec![a, b], Some(d)),
+    Instruction::JumpIfTrue((a, b)) | Instruction::JumpIfFalse((a, b)) => (vec![a, b], None),
+    Instruction::ReduceAbsoluteRelative(a) | Instruction::Output(a) => (vec![a], None),
+    Instruction::Input(d) => (Vec::new(), Some(d)),
+    Instruction::Halt => (Vec::new(), None),
+  }
+}
+
+fn relative(offset: i64) -> String {
+  match offset {
+    0 => "rb".to_owned(),
+    offset if offset > 0 => format!("rb+{}", offset),
+    offset => format!("rb{}", offset),
+  }
+}
+
+pub fn disassemble(instruction: &Instruction) -> String {
+  let name = match instruction {
+    Instruction::Add(_) => "add",
+    Instruction::Multiply(_) => "mul",
+    Instruction::Input(_) => "in",
+    Instruction::Output(_) => "out",
+    Instruction::JumpIfTrue(_) => "jt",
+    Instruction::JumpIfFalse(_) => "jf",
+    Instruction::LessThan(_) => "lt",
+    Instruction::Equals(_) => "eq",
+    Instruction::ReduceAbsoluteRelative(_) => "arb",
+    Instruction::Halt => "hlt",
+  };
+  let (sources, dest) = operands(instruction);
+  let mut words: Vec<String> = sources
+    .into_iter()
+    .map(|value| match value {
+      Value::Direct(value) => format!("#{}", value),
+      Value::Stored(addr) => addr.to_string(),
+      Value::Relative(offset) => relative(*offset),
+    })
+    .collect();
+  words.extend(dest.map(|value| match value {
+    Value::Direct(addr) => addr.to_string(),
+    Value::Stored(addr) => format!("#{}", addr),
+    Value::Relative(offset) => relative(*offset),
+  }));
+  if words.is_empty() {
+    name.to_owned()
+  } else {
+    format!("{} {}", name, words.join(", "))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn viewer() -> Viewer {
+    Viewer::new(Computer::load(
+      0,
+      &[3, 13, 1002, 13, 3, 13, 109, 1, 204, 12, 1105, 1, 0, 0],
+    ))
+  }
+
+  #[test]
+  fn step_1() {
+    let mut viewer = viewer().with_input(&[5]);
+    for _ in 0..5 {
+      assert!(viewer.step());
+    }
+    assert_eq!(viewer.outputs().cloned().collect::<Vec<_>>(), vec![15]);
+    assert!(!viewer.step());
+    assert_eq!(viewer.status(), &Status::Waiting(0));
+    assert!(!viewer.step());
+    for key in &[Key::Char('-'), Key::Char('2'), Key::Enter] {
+      assert!(viewer.handle(*key));
+    }
+    assert!(viewer.step());
+    assert_eq!(viewer.status(), &Status::Ready);
+    assert_eq!(viewer.computer().read_memory(13), Some(-6));
+    assert!(!viewer.handle(Key::Char('q')));
+  }
+  #[test]
+  fn tick_speed() {
+    let mut viewer = Viewer::new(Computer::load(0, &[1101, 1, 2, 5, 99, 0])).with_speed(8);
+    viewer.tick();
+    assert_eq!(viewer.status(), &Status::Ready);
+    viewer.handle(Key::Char(' '));
+    viewer.tick();
+    assert_eq!(viewer.status(), &Status::Halted);
+    assert_eq!(viewer.computer().read_memory(5), Some(3));
+    viewer.handle(Key::Char('<'));
+    assert_eq!(viewer.speed, 4);
+  }
+  #[test]
+  fn render_1() {
+    let mut viewer = viewer().with_input(&[7]);
+    viewer.step();
+    viewer.step();
+    viewer.step();
+    let lines = viewer.render(60, 10);
+    assert_eq!(lines.len(), 10);
+    assert!(lines.iter().all(|line| line.chars().count() == 60));
+    assert_eq!(
+      lines[0].trim_end(),
+      "pc 8  rb 1  steps 3  speed 1/tick  paused"
+    );
+    let listing: Vec<&str> = lines[1..7].iter().map(|line| &line[..30]).collect();
+    assert_eq!(
+      listing,
+      vec![
+        "      2  mul 13, #3, 13       ",
+        "      6  arb #1               ",
+        ">     8  out rb+12            ",
+        "     10  jt #1, #0            ",
+        "     13  data 21              ",
+        "     14  data ?               ",
+      ]
+    );
+    assert_eq!(&lines[1][30..], " | memory 0..14, 1 cells per c");
+    assert_eq!(lines[2][30..].trim_end(), " | ........     +");
+    assert_eq!(lines[7].trim_end(), "out:");
+    viewer.handle(Key::Char('4'));
+    assert_eq!(viewer.render(60, 10)[8].trim_end(), "in [] > 4_");
+  }
+}
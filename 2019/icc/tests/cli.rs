@@ -0,0 +1,68 @@
+use std::process::Command;
+
+fn dump(path: &std::path::Path, extra: &[&str]) -> (String, String) {
+  icc(&["dump", path.to_str().unwrap()], extra)
+}
+
+fn icc(command: &[&str], extra: &[&str]) -> (String, String) {
+  let output = Command::new(env!("CARGO_BIN_EXE_icc"))
+    .args(command)
+    .args(extra)
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  (
+    String::from_utf8(output.stdout).unwrap(),
+    String::from_utf8(output.stderr).unwrap(),
+  )
+}
+
+#[test]
+fn snapshot_round_trip() {
+  let dir = std::env::temp_dir().join(format!("icc-cli-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let program = dir.join("program.csv");
+  std::fs::write(&program, "3,9,1001,9,1,9,4,9,99,0").unwrap();
+  let (text, diagnostics) = dump(&program, &["--snapshot", "-i", "41"]);
+  assert!(diagnostics.contains("output: 42"), "{}", diagnostics);
+  let snapshot = icc::loader::from_str(&text).unwrap();
+  assert_eq!(snapshot.get(9), Some(42));
+  let saved = dir.join("program.snap");
+  std::fs::write(&saved, &text).unwrap();
+  let (reloaded, _) = dump(&saved, &["--snapshot"]);
+  assert_eq!(icc::loader::from_str(&reloaded).unwrap(), snapshot);
+  let (blocked, _) = dump(&program, &["--snapshot"]);
+  assert!(blocked.starts_with("snapshot pc=2 rb=0 image=10 input=9\n"), "{}", blocked);
+  std::fs::write(&saved, &blocked).unwrap();
+  let (_, diagnostics) = dump(&saved, &["-i", "41"]);
+  assert!(diagnostics.contains("output: 42"), "{}", diagnostics);
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn diff_snapshots() {
+  let dir = std::env::temp_dir().join(format!("icc-diff-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let before = dir.join("before.snap");
+  let after = dir.join("after.snap");
+  std::fs::write(&before, "snapshot pc=0 rb=0 image=4\n0: 1101 2 3 4\n").unwrap();
+  std::fs::write(&after, "snapshot pc=4 rb=0 image=4\n0: 1101 7 3 4\n-2: 9\n").unwrap();
+  let paths = [before.to_str().unwrap(), after.to_str().unwrap()];
+  let (diff, _) = icc(&["diff"], &paths);
+  assert_eq!(diff, "created -2: 9\ncode    1: 2 -> 7\n");
+  let (hex, _) = icc(&["diff", "--hex"], &paths);
+  assert_eq!(hex, "created -00002: 9\ncode    000001: 2 -> 7\n");
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn bad_speed() {
+  let output = Command::new(env!("CARGO_BIN_EXE_icc"))
+    .args(["tui", "../day/02/input.csv", "--speed", "fast"])
+    .output()
+    .unwrap();
+  assert_eq!(output.status.code(), Some(1));
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("'fast' isn't a valid value"), "{}", stderr);
+  assert!(!stderr.contains("panicked"), "{}", stderr);
+}
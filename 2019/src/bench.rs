@@ -0,0 +1,211 @@
+use crate::report::{markdown_cell, Format};
+use helpers::Part;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+  Parse,
+  Solve(Part),
+}
+impl std::fmt::Display for Stage {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Stage::Parse => write!(fmt, "parse"),
+      Stage::Solve(part) => write!(fmt, "{}", part),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+  pub year: u32,
+  pub day: u32,
+  pub stage: Stage,
+  pub min: Duration,
+  pub median: Duration,
+  pub max: Duration,
+  pub runs: usize,
+}
+
+impl Row {
+  pub fn new(year: u32, day: u32, stage: Stage, samples: &[Duration]) -> Option<Row> {
+    let mut samples = samples.to_vec();
+    samples.sort();
+    let runs = samples.len();
+    Some(Row {
+      year,
+      day,
+      stage,
+      min: *samples.first()?,
+      median: if runs % 2 == 1 {
+        samples[runs / 2]
+      } else {
+        (samples[runs / 2 - 1] + samples[runs / 2]) / 2
+      },
+      max: *samples.last()?,
+      runs,
+    })
+  }
+}
+
+pub fn duration(time: Duration) -> String {
+  let nanos = time.as_nanos() as f64;
+  if nanos < 1e3 {
+    format!("{}ns", nanos)
+  } else if nanos < 1e6 {
+    format!("{:.1}µs", nanos / 1e3)
+  } else if nanos < 1e9 {
+    format!("{:.1}ms", nanos / 1e6)
+  } else {
+    format!("{:.2}s", nanos / 1e9)
+  }
+}
+
+pub fn report(rows: &[Row], format: Format) -> String {
+  match format {
+    Format::Table => table(rows),
+    Format::Json => json(rows),
+    Format::Csv => csv(rows),
+    Format::Markdown => markdown(rows),
+  }
+}
+
+fn table(rows: &[Row]) -> String {
+  let mut lines = vec![format!(
+    "{:<4}{:<6}{:>5}{:>10}{:>10}{:>10}",
+    "day", "stage", "runs", "min", "median", "max"
+  )];
+  for row in rows {
+    lines.push(format!(
+      "{:<4}{:<6}{:>5}{:>10}{:>10}{:>10}",
+      format!("{:02}", row.day),
+      row.stage.to_string(),
+      row.runs,
+      duration(row.min),
+      duration(row.median),
+      duration(row.max)
+    ));
+  }
+  lines.join("\n")
+}
+
+fn json(rows: &[Row]) -> String {
+  let records: Vec<String> = rows
+    .iter()
+    .map(|row| {
+      format!(
+        "  {{\"year\": {}, \"day\": {}, \"stage\": \"{}\", \"runs\": {}, \"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}}}",
+        row.year,
+        row.day,
+        row.stage,
+        row.runs,
+        row.min.as_nanos(),
+        row.median.as_nanos(),
+        row.max.as_nanos()
+      )
+    })
+    .collect();
+  if records.is_empty() {
+    "[]".into()
+  } else {
+    format!("[\n{}\n]", records.join(",\n"))
+  }
+}
+
+fn csv(rows: &[Row]) -> String {
+  let mut lines = vec!["year,day,stage,runs,min_ns,median_ns,max_ns".to_owned()];
+  for row in rows {
+    lines.push(format!(
+      "{},{},{},{},{},{},{}",
+      row.year,
+      row.day,
+      row.stage,
+      row.runs,
+      row.min.as_nanos(),
+      row.median.as_nanos(),
+      row.max.as_nanos()
+    ));
+  }
+  lines.join("\n")
+}
+
+fn markdown(rows: &[Row]) -> String {
+  let mut lines = vec![
+    "| year | day | stage | runs | min | median | max |".to_owned(),
+    "| ---: | --: | :---: | ---: | --: | -----: | --: |".to_owned(),
+  ];
+  for row in rows {
+    lines.push(format!(
+      "| {} | {} | {} | {} | {} | {} | {} |",
+      row.year,
+      row.day,
+      markdown_cell(&row.stage.to_string()),
+      row.runs,
+      duration(row.min),
+      duration(row.median),
+      duration(row.max)
+    ));
+  }
+  lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn micros(values: &[u64]) -> Vec<Duration> {
+    values.iter().map(|v| Duration::from_micros(*v)).collect()
+  }
+
+  #[test]
+  fn stats() {
+    let row = Row::new(2019, 1, Stage::Parse, &micros(&[30, 10, 20])).unwrap();
+    assert_eq!(
+      (row.min, row.median, row.max, row.runs),
+      (
+        Duration::from_micros(10),
+        Duration::from_micros(20),
+        Duration::from_micros(30),
+        3
+      )
+    );
+    let row = Row::new(2019, 1, Stage::Parse, &micros(&[40, 10, 20, 30])).unwrap();
+    assert_eq!(row.median, Duration::from_micros(25));
+    assert_eq!(Row::new(2019, 1, Stage::Parse, &[]), None);
+  }
+  #[test]
+  fn formats() {
+    let rows = vec![
+      Row::new(2019, 1, Stage::Parse, &micros(&[12])).unwrap(),
+      Row::new(2019, 12, Stage::Solve(Part::B), &micros(&[2_500_000])).unwrap(),
+    ];
+    assert_eq!(
+      report(&rows, Format::Table),
+      [
+        "day stage  runs       min    median       max",
+        "01  parse     1    12.0µs    12.0µs    12.0µs",
+        "12  b         1     2.50s     2.50s     2.50s",
+      ]
+      .join("\n")
+    );
+    assert_eq!(
+      report(&rows, Format::Csv),
+      "year,day,stage,runs,min_ns,median_ns,max_ns\n2019,1,parse,1,12000,12000,12000\n2019,12,b,1,2500000000,2500000000,2500000000"
+    );
+    assert!(report(&rows, Format::Json).starts_with(
+      "[\n  {\"year\": 2019, \"day\": 1, \"stage\": \"parse\", \"runs\": 1, \"min_ns\": 12000,"
+    ));
+    assert_eq!(
+      report(&rows[1..], Format::Markdown),
+      [
+        "| year | day | stage | runs | min | median | max |",
+        "| ---: | --: | :---: | ---: | --: | -----: | --: |",
+        "| 2019 | 12 | b | 1 | 2.50s | 2.50s | 2.50s |",
+      ]
+      .join("\n")
+    );
+    assert_eq!(report(&[], Format::Json), "[]");
+    assert_eq!(duration(Duration::from_nanos(999)), "999ns");
+    assert_eq!(duration(Duration::from_micros(1500)), "1.5ms");
+  }
+}
@@ -1,41 +1,511 @@
-use clap::{App, Arg};
-use helpers::Solvers;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use helpers::answers::Answers;
+use helpers::input::{year_dir, Override, Resolver};
+use helpers::rejected::{Conflict, Rejected, Verdict};
+use helpers::{Part, Registry};
+use report::{Format, Record};
+use runner::{Failure, Limits, Summary};
+use std::sync::Arc;
+use std::time::Duration;
+use verify::Check;
+
+mod bench;
+mod report;
+mod runner;
+mod select;
+mod verify;
+
+const ANSWERS: &str = "answers.txt";
+const REJECTED: &str = "rejected.txt";
+
+fn registry() -> Registry {
+  let mut registry = Registry::new();
+  registry.register(2019, 1, Some("day/01/input.csv"), day01::Day01);
+  registry.register(2019, 2, Some("day/02/input.csv"), day02::Day02);
+  registry.register(2019, 3, Some("day/03/input.csv"), day03::Day03);
+  registry.register(2019, 4, Some("day/04/input.txt"), day04::Day04);
+  registry.register(2019, 5, Some("day/05/input.csv"), day05::Day05);
+  registry.register(2019, 6, Some("day/06/input.csv"), day06::Day06);
+  registry.register(2019, 7, Some("day/07/input.csv"), day07::Day07);
+  registry.register(2019, 8, Some("day/08/input.csv"), day08::Day08);
+  registry.register(2019, 9, Some("day/09/input.csv"), day09::Day09);
+  registry.register(2019, 10, Some("day/10/input.map"), day10::Day10);
+  registry.register(2019, 11, Some("day/11/input.csv"), day11::Day11);
+  registry.register(2019, 12, Some("day/12/input.tuples"), day12::Day12);
+  registry.register(2019, 13, None, day13::Day13);
+  registry.register(2019, 14, Some("day/14/input.cmds"), day14::Day14);
+  registry
+}
+
+fn year_arg() -> Arg<'static, 'static> {
+  Arg::with_name("year")
+    .long("year")
+    .short("y")
+    .takes_value(true)
+    .help("Year to run, defaults to the latest registered one")
+}
+
+fn selection_args() -> Vec<Arg<'static, 'static>> {
+  vec![
+    year_arg(),
+    Arg::with_name("day")
+      .long("day")
+      .short("d")
+      .takes_value(true)
+      .help("Days to run, e.g. 7, 3..7, 3..=7 or 1,4,9"),
+    Arg::with_name("part")
+      .long("part")
+      .short("p")
+      .takes_value(true)
+      .help("Part to run, a or b"),
+    Arg::with_name("input-root")
+      .long("input-root")
+      .takes_value(true)
+      .help("Directory holding the YEAR/day/NN/ inputs, defaults to $AOC_INPUT_ROOT"),
+  ]
+}
+
+fn limit_args() -> Vec<Arg<'static, 'static>> {
+  vec![
+    Arg::with_name("jobs")
+      .long("jobs")
+      .short("j")
+      .takes_value(true)
+      .help("Number of days solved at once, defaults to the number of CPUs"),
+    Arg::with_name("timeout")
+      .long("timeout")
+      .takes_value(true)
+      .value_name("SECS")
+      .help("Gives up on a parse or part after this many seconds"),
+  ]
+}
 
 fn main() {
-  let mut solutions: Vec<Solvers> = vec![
-    day14::solve(),
-    day13::solve(),
-    day12::solve(),
-    day11::solve(),
-    day10::solve(),
-    // day09::solve(),
-    // day08::solve(),
-    // day07::solve(),
-    // day06::solve(),
-    // day05::solve(),
-    // day04::solve(),
-    // day03::solve(),
-    // day02::solve(),
-    // day01::solve(),
-  ];
-  solutions.reverse();
   let matches = App::new("Advent of Code")
-    .version("2019")
-    .arg(Arg::with_name("all").long("all").short("a"))
+    .version(env!("CARGO_PKG_VERSION"))
+    .setting(AppSettings::ArgsNegateSubcommands)
+    .arg(
+      Arg::with_name("all")
+        .long("all")
+        .short("a")
+        .conflicts_with("day")
+        .help("Runs every registered day"),
+    )
+    .args(&selection_args())
+    .args(&limit_args())
+    .arg(
+      Arg::with_name("input")
+        .long("input")
+        .short("i")
+        .takes_value(true)
+        .conflicts_with("all")
+        .help("Reads the input of a single day from this file, - for stdin"),
+    )
+    .arg(
+      Arg::with_name("time")
+        .long("time")
+        .short("t")
+        .help("Shows how long parsing and each part took"),
+    )
+    .arg(
+      Arg::with_name("bench")
+        .long("bench")
+        .takes_value(true)
+        .value_name("N")
+        .help("Runs every selected day N times and reports min/median/max"),
+    )
+    .arg(
+      Arg::with_name("format")
+        .long("format")
+        .takes_value(true)
+        .help("Prints the answers or benchmark as table, json, csv or markdown"),
+    )
+    .subcommand(
+      SubCommand::with_name("list")
+        .about("Lists the registered days and which parts are stubs")
+        .arg(year_arg().help("Only lists this year")),
+    )
+    .subcommand(
+      SubCommand::with_name("verify")
+        .about("Checks every selected day against the known answers")
+        .args(&selection_args())
+        .args(&limit_args())
+        .arg(
+          Arg::with_name("answers")
+            .long("answers")
+            .takes_value(true)
+            .help("Answers file, defaults to answers.txt next to the inputs"),
+        ),
+    )
+    .subcommand(
+      SubCommand::with_name("reject")
+        .about("Records an answer the puzzle site turned down")
+        .args(&selection_args())
+        .arg(
+          Arg::with_name("high")
+            .long("high")
+            .conflicts_with("low")
+            .help("The answer was too high"),
+        )
+        .arg(
+          Arg::with_name("low")
+            .long("low")
+            .help("The answer was too low"),
+        )
+        .arg(Arg::with_name("answer").required(true)),
+    )
     .get_matches();
 
-  if matches.is_present("all") {
-    println!("This may take a while ...");
-    solutions.iter().enumerate().for_each(|(idx, s)| {
-      print(s, idx + 1);
-    });
-  } else if let Some(latest) = solutions.pop() {
-    let idx = solutions.len();
-    print(&latest, idx + 1);
+  let registry = Arc::new(registry());
+  match matches.subcommand() {
+    ("list", Some(matches)) => list(&registry, matches),
+    ("verify", Some(matches)) => std::process::exit(verify(&registry, matches)),
+    ("reject", Some(matches)) => reject(&registry, matches),
+    _ => std::process::exit(run(&registry, &matches)),
+  }
+}
+
+fn list(registry: &Registry, matches: &ArgMatches) {
+  let year = matches.value_of("year").map(|_| year(registry, matches));
+  for entry in registry
+    .iter()
+    .filter(|entry| year.is_none_or(|year| entry.year == year))
+  {
+    let implemented = entry.puzzle.parts();
+    let parts: Vec<String> = [Part::A, Part::B]
+      .iter()
+      .map(|part| {
+        if implemented.contains(part) {
+          part.to_string()
+        } else {
+          format!("{} (stub)", part)
+        }
+      })
+      .collect();
+    println!("{} day {:02}: {}", entry.year, entry.day, parts.join(", "));
+  }
+}
+
+fn run(registry: &Arc<Registry>, matches: &ArgMatches) -> i32 {
+  let format = match matches.value_of("format").map(report::parse_format) {
+    None => Format::Table,
+    Some(Ok(format)) => format,
+    Some(Err(err)) => fail(&err),
+  };
+  let table = format == Format::Table;
+  let year = year(registry, matches);
+  let latest = registry.days(year).max().into_iter().collect();
+  let default = if matches.is_present("all") {
+    if table {
+      println!("This may take a while ...");
+    }
+    registry.days(year).collect()
+  } else {
+    latest
+  };
+  let (days, parts) = selection(registry, year, matches, default);
+  let limits = limits(matches);
+  let mut resolver = resolver(matches, year);
+  let (_, rejected) = rejected(&resolver, year);
+  match matches.value_of("input") {
+    Some(_) if days.len() > 1 => fail("--input can only be used with a single day"),
+    Some("-") => resolver = resolver.with_override(Override::Stdin),
+    Some(path) => resolver = resolver.with_override(Override::Path(path.into())),
+    None => (),
+  }
+  if let Some(runs) = matches.value_of("bench") {
+    let runs = match runs.parse::<usize>() {
+      Ok(runs) if runs > 0 => runs,
+      _ => fail(&format!("'{}' is not a number of runs", runs)),
+    };
+    let rows = benchmark(registry, year, &days, &parts, &resolver, limits, runs);
+    println!("{}", bench::report(&rows, format));
+    return 0;
+  }
+  let time = matches.is_present("time");
+  let mut summary = Summary::default();
+  let mut records = Vec::new();
+  let mut hidden = false;
+  runner::run_all(
+    registry,
+    year,
+    &days,
+    &parts,
+    &resolver,
+    limits,
+    |solution| {
+      summary.add(&solution);
+      let day = solution.day;
+      if let (true, true, Some(parse)) = (table, time, solution.parse) {
+        println!("[{:02}] parsed in {}", day, bench::duration(parse));
+      }
+      // Without a parsed input every part failed the same way, say it once.
+      let unparsed = solution.parse.is_none();
+      if let (true, Some(Err(failure))) = (unparsed, solution.outcomes.first().map(|o| &o.result)) {
+        hidden |= report_failure(&format!("{:02}", day), failure);
+      }
+      for outcome in solution.outcomes {
+        records.push(Record::new(year, day, &outcome));
+        let part = outcome.part;
+        let answer = match outcome.result {
+          Ok(answer) => answer,
+          Err(_) if unparsed => continue,
+          Err(failure) => {
+            hidden |= report_failure(&format!("{:02}:{}", day, part), &failure);
+            continue;
+          }
+        };
+        match (table, time) {
+          (false, _) => (),
+          (true, true) => {
+            let time = bench::duration(outcome.time);
+            println!("[{:02}:{}] {} ({})", day, part, answer, time);
+          }
+          (true, false) => println!("[{:02}:{}] {}", day, part, answer),
+        }
+        for conflict in rejected.check(year, day, part, &answer) {
+          eprintln!("[{:02}:{}] warning: {}", day, part, conflict);
+        }
+      }
+    },
+  );
+  if hidden {
+    eprintln!("note: run with `RUST_BACKTRACE=1` to show where solvers panicked");
+  }
+  if !table {
+    println!("{}", report::report(&records, format));
+  } else if days.len() > 1 {
+    println!("{}", summary);
+  }
+  if summary.failed.is_empty() {
+    0
+  } else {
+    1
+  }
+}
+
+// Returns whether a panic's backtrace was left out.
+fn report_failure(label: &str, failure: &Failure) -> bool {
+  eprintln!("[{}] {}", label, failure);
+  match failure {
+    Failure::Panic(panic) => match &panic.backtrace {
+      Some(backtrace) => {
+        eprintln!("{}", backtrace.trim_end());
+        false
+      }
+      None => true,
+    },
+    _ => false,
+  }
+}
+
+// Runs one day at a time so the samples are not skewed by each other.
+fn benchmark(
+  registry: &Arc<Registry>,
+  year: u32,
+  days: &[u32],
+  parts: &[Part],
+  resolver: &Resolver,
+  limits: Limits,
+  runs: usize,
+) -> Vec<bench::Row> {
+  let mut rows = Vec::new();
+  for day in days.iter().cloned() {
+    let text = match resolver.read(registry.get(year, day).and_then(|entry| entry.input)) {
+      Ok(text) => text,
+      Err(err) => {
+        eprintln!("[{:02}] {}", day, err);
+        continue;
+      }
+    };
+    let mut parse = Vec::new();
+    let mut times = vec![Vec::new(); parts.len()];
+    for run in 0..runs {
+      let solution = runner::solve_text(registry, year, day, parts, text.clone(), limits.timeout);
+      parse.extend(solution.parse);
+      for (idx, outcome) in solution.outcomes.into_iter().enumerate() {
+        match outcome.result {
+          Ok(_) => times[idx].push(outcome.time),
+          Err(err) if run == 0 => eprintln!("[{:02}:{}] {}", day, outcome.part, err),
+          Err(_) => (),
+        }
+      }
+      if solution.parse.is_none() {
+        break;
+      }
+    }
+    rows.extend(bench::Row::new(year, day, bench::Stage::Parse, &parse));
+    for (part, samples) in parts.iter().zip(&times) {
+      rows.extend(bench::Row::new(
+        year,
+        day,
+        bench::Stage::Solve(*part),
+        samples,
+      ));
+    }
+  }
+  rows
+}
+
+fn reject(registry: &Registry, matches: &ArgMatches) {
+  let year = year(registry, matches);
+  let (days, parts) = selection(registry, year, matches, vec![]);
+  let (day, part) = match (days.as_slice(), parts.as_slice()) {
+    ([day], [part]) => (*day, *part),
+    _ => fail("reject needs a single --day and --part"),
+  };
+  let verdict = if matches.is_present("high") {
+    Verdict::TooHigh
+  } else if matches.is_present("low") {
+    Verdict::TooLow
+  } else {
+    Verdict::Wrong
+  };
+  let answer = matches.value_of("answer").unwrap_or_default();
+  let (path, log) = rejected(&resolver(matches, year), year);
+  let conflicts = log.check(year, day, part, answer);
+  if conflicts.iter().any(|c| matches!(c, Conflict::Rejected(_))) {
+    println!("[{:02}:{}] {} is already recorded", day, part, answer);
+    return;
+  }
+  let mut line = Rejected::new();
+  line.record(year, day, part, verdict, answer);
+  let written = std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&path)
+    .and_then(|mut file| std::io::Write::write_all(&mut file, line.to_string().as_bytes()));
+  if let Err(err) = written {
+    fail(&format!("could not write {}: {}", path.display(), err));
+  }
+}
+
+// The log lives next to the inputs, or in the year's directory if there is
+// none yet.
+fn rejected(resolver: &Resolver, year: u32) -> (std::path::PathBuf, Rejected) {
+  let path = match resolver.locate(REJECTED) {
+    Ok(path) => path,
+    Err(_) => return (year_dir(year).join(REJECTED), Rejected::new()),
+  };
+  let log = std::fs::read_to_string(&path)
+    .map_err(|err| err.to_string())
+    .and_then(|text| Rejected::parse(&text).map_err(|err| err.to_string()));
+  match log {
+    Ok(log) => (path, log),
+    Err(err) => {
+      eprintln!("warning: ignoring {}: {}", path.display(), err);
+      (path, Rejected::new())
+    }
+  }
+}
+
+fn verify(registry: &Arc<Registry>, matches: &ArgMatches) -> i32 {
+  let year = year(registry, matches);
+  let (days, parts) = selection(registry, year, matches, registry.days(year).collect());
+  let resolver = resolver(matches, year);
+  let text = match matches.value_of("answers") {
+    Some(path) => Resolver::new()
+      .with_override(Override::Path(path.into()))
+      .read(None),
+    None => resolver.read(Some(ANSWERS)),
+  };
+  let answers = match text.map(|text| Answers::parse(&text)) {
+    Ok(Ok(answers)) => answers,
+    Ok(Err(err)) => fail(&err.to_string()),
+    Err(err) => fail(&err.to_string()),
+  };
+  let (_, rejected) = rejected(&resolver, year);
+  let mut checks = Vec::new();
+  let limits = limits(matches);
+  runner::run_all(
+    registry,
+    year,
+    &days,
+    &parts,
+    &resolver,
+    limits,
+    |solution| {
+      for outcome in solution.outcomes {
+        checks.push(Check::new(
+          &answers,
+          &rejected,
+          solution.year,
+          solution.day,
+          outcome.part,
+          outcome.result,
+        ));
+      }
+    },
+  );
+  println!("{}", verify::table(&checks));
+  if checks.iter().any(Check::failed) {
+    1
+  } else {
+    0
+  }
+}
+
+fn year(registry: &Registry, matches: &ArgMatches) -> u32 {
+  let years = registry.years();
+  let year = match matches.value_of("year").map(str::parse::<u32>) {
+    None => years.last().cloned().unwrap_or_default(),
+    Some(Ok(year)) => year,
+    Some(Err(_)) => fail("--year needs a year such as 2019"),
+  };
+  if !years.contains(&year) {
+    fail(&format!("{} has no registered days, see `aoc list`", year));
+  }
+  year
+}
+
+fn selection(
+  registry: &Registry,
+  year: u32,
+  matches: &ArgMatches,
+  default: Vec<u32>,
+) -> (Vec<u32>, Vec<Part>) {
+  let parts = match matches.value_of("part").map(select::parse_part) {
+    None => vec![Part::A, Part::B],
+    Some(Ok(parts)) => parts,
+    Some(Err(err)) => fail(&err),
+  };
+  let days = match matches.value_of("day").map(select::parse_days) {
+    None => default,
+    Some(Ok(days)) => days,
+    Some(Err(err)) => fail(&err),
+  };
+  if let Some(day) = days.iter().find(|day| registry.get(year, **day).is_none()) {
+    fail(&format!(
+      "{} day {} is not registered, see `aoc list`",
+      year, day
+    ));
+  }
+  (days, parts)
+}
+
+fn limits(matches: &ArgMatches) -> Limits {
+  let jobs = match matches.value_of("jobs").map(str::parse::<usize>) {
+    None => std::thread::available_parallelism().map_or(1, usize::from),
+    Some(Ok(jobs)) if jobs > 0 => jobs,
+    Some(_) => fail("--jobs needs a positive number"),
+  };
+  let timeout = match matches.value_of("timeout").map(str::parse::<f64>) {
+    None => None,
+    Some(Ok(secs)) if secs > 0.0 && secs.is_finite() => Some(Duration::from_secs_f64(secs)),
+    Some(_) => fail("--timeout needs a positive number of seconds"),
+  };
+  Limits { jobs, timeout }
+}
+
+fn resolver(matches: &ArgMatches, year: u32) -> Resolver {
+  let resolver = Resolver::for_year(year);
+  match matches.value_of("input-root") {
+    Some(root) => resolver.with_year_root(root, year),
+    None => resolver,
   }
 }
 
-fn print(solver: &Solvers, day: usize) {
-  println!("[{:02}:a] {}", day, solver.a());
-  println!("[{:02}:b] {}", day, solver.b());
+fn fail(message: &str) -> ! {
+  eprintln!("error: {}", message);
+  std::process::exit(2);
 }
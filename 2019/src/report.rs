@@ -0,0 +1,267 @@
+use crate::runner::{Failure, Outcome};
+use helpers::{Part, SolveError};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+  Table,
+  Json,
+  Csv,
+  Markdown,
+}
+
+pub fn parse_format(spec: &str) -> Result<Format, String> {
+  match spec.trim() {
+    "table" => Ok(Format::Table),
+    "json" => Ok(Format::Json),
+    "csv" => Ok(Format::Csv),
+    "markdown" | "md" => Ok(Format::Markdown),
+    other => Err(format!(
+      "'{}' is not a format, use table, json, csv or markdown",
+      other
+    )),
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+  Ok,
+  Failed,
+  Stub,
+  Timeout,
+}
+impl std::fmt::Display for Status {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Status::Ok => write!(fmt, "ok"),
+      Status::Failed => write!(fmt, "failed"),
+      Status::Stub => write!(fmt, "stub"),
+      Status::Timeout => write!(fmt, "timeout"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+  pub year: u32,
+  pub day: u32,
+  pub part: Part,
+  pub answer: Option<String>,
+  pub time: Duration,
+  pub status: Status,
+}
+
+impl Record {
+  pub fn new(year: u32, day: u32, outcome: &Outcome) -> Record {
+    let status = match &outcome.result {
+      Ok(_) => Status::Ok,
+      Err(Failure::Solve(SolveError::Unimplemented)) => Status::Stub,
+      Err(Failure::Timeout(_)) => Status::Timeout,
+      Err(_) => Status::Failed,
+    };
+    Record {
+      year,
+      day,
+      part: outcome.part,
+      answer: outcome.result.as_ref().ok().cloned(),
+      time: outcome.time,
+      status,
+    }
+  }
+}
+
+pub fn json_string(text: &str) -> String {
+  let mut quoted = String::from("\"");
+  for c in text.chars() {
+    match c {
+      '"' => quoted.push_str("\\\""),
+      '\\' => quoted.push_str("\\\\"),
+      '\n' => quoted.push_str("\\n"),
+      c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+      c => quoted.push(c),
+    }
+  }
+  quoted.push('"');
+  quoted
+}
+
+pub fn csv_field(text: &str) -> String {
+  if text.contains(&[',', '"', '\n'][..]) {
+    format!("\"{}\"", text.replace('"', "\"\""))
+  } else {
+    text.to_owned()
+  }
+}
+
+pub fn markdown_cell(text: &str) -> String {
+  text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+pub fn report(records: &[Record], format: Format) -> String {
+  match format {
+    Format::Table => table(records),
+    Format::Json => json(records),
+    Format::Csv => csv(records),
+    Format::Markdown => markdown(records),
+  }
+}
+
+fn table(records: &[Record]) -> String {
+  let lines: Vec<String> = records
+    .iter()
+    .map(|record| {
+      let line = format!(
+        "[{:02}:{}] {:<8}{}",
+        record.day,
+        record.part,
+        record.status.to_string(),
+        record.answer.as_deref().unwrap_or_default()
+      );
+      line.trim_end().to_owned()
+    })
+    .collect();
+  lines.join("\n")
+}
+
+fn json(records: &[Record]) -> String {
+  let records: Vec<String> = records
+    .iter()
+    .map(|record| {
+      format!(
+        "  {{\"year\": {}, \"day\": {}, \"part\": \"{}\", \"answer\": {}, \"time_ns\": {}, \"status\": \"{}\"}}",
+        record.year,
+        record.day,
+        record.part,
+        record
+          .answer
+          .as_deref()
+          .map_or("null".to_owned(), json_string),
+        record.time.as_nanos(),
+        record.status
+      )
+    })
+    .collect();
+  if records.is_empty() {
+    "[]".into()
+  } else {
+    format!("[\n{}\n]", records.join(",\n"))
+  }
+}
+
+fn csv(records: &[Record]) -> String {
+  let mut lines = vec!["year,day,part,answer,time_ns,status".to_owned()];
+  for record in records {
+    lines.push(format!(
+      "{},{},{},{},{},{}",
+      record.year,
+      record.day,
+      record.part,
+      csv_field(record.answer.as_deref().unwrap_or_default()),
+      record.time.as_nanos(),
+      record.status
+    ));
+  }
+  lines.join("\n")
+}
+
+fn markdown(records: &[Record]) -> String {
+  let mut lines = vec![
+    "| year | day | part | answer | time | status |".to_owned(),
+    "| ---: | --: | :--: | ------ | ---: | ------ |".to_owned(),
+  ];
+  for record in records {
+    lines.push(format!(
+      "| {} | {} | {} | {} | {} | {} |",
+      record.year,
+      record.day,
+      record.part,
+      markdown_cell(record.answer.as_deref().unwrap_or_default()),
+      crate::bench::duration(record.time),
+      record.status
+    ));
+  }
+  lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn outcome(part: Part, result: Result<&str, Failure>) -> Outcome {
+    Outcome {
+      part,
+      result: result.map(str::to_owned),
+      time: Duration::from_micros(5),
+    }
+  }
+
+  #[test]
+  fn records() {
+    let records = vec![
+      Record::new(2019, 1, &outcome(Part::A, Ok("42"))),
+      Record::new(2019, 8, &outcome(Part::B, Ok("#, \"|\n#"))),
+      Record::new(
+        2019,
+        12,
+        &outcome(Part::B, Err(Failure::Timeout(Duration::from_secs(1)))),
+      ),
+      Record::new(
+        2019,
+        13,
+        &outcome(Part::A, Err(Failure::Solve(SolveError::Unimplemented))),
+      ),
+      Record::new(
+        2019,
+        14,
+        &outcome(Part::A, Err(Failure::Input("missing".into()))),
+      ),
+    ];
+    assert_eq!(
+      records.iter().map(|r| r.status).collect::<Vec<Status>>(),
+      vec![
+        Status::Ok,
+        Status::Ok,
+        Status::Timeout,
+        Status::Stub,
+        Status::Failed
+      ]
+    );
+    assert_eq!(
+      report(&records[..2], Format::Json),
+      [
+        "[",
+        "  {\"year\": 2019, \"day\": 1, \"part\": \"a\", \"answer\": \"42\", \"time_ns\": 5000, \"status\": \"ok\"},",
+        "  {\"year\": 2019, \"day\": 8, \"part\": \"b\", \"answer\": \"#, \\\"|\\n#\", \"time_ns\": 5000, \"status\": \"ok\"}",
+        "]",
+      ]
+      .join("\n")
+    );
+    assert!(report(&records, Format::Json)
+      .contains("\"answer\": null, \"time_ns\": 5000, \"status\": \"timeout\""));
+    assert_eq!(
+      report(&records[1..4], Format::Csv),
+      "year,day,part,answer,time_ns,status\n2019,8,b,\"#, \"\"|\n#\",5000,ok\n2019,12,b,,5000,timeout\n2019,13,a,,5000,stub"
+    );
+    assert_eq!(
+      report(&records[1..3], Format::Markdown),
+      [
+        "| year | day | part | answer | time | status |",
+        "| ---: | --: | :--: | ------ | ---: | ------ |",
+        "| 2019 | 8 | b | #, \"\\|<br># | 5.0µs | ok |",
+        "| 2019 | 12 | b |  | 5.0µs | timeout |",
+      ]
+      .join("\n")
+    );
+    assert_eq!(
+      report(&records[2..], Format::Table),
+      "[12:b] timeout\n[13:a] stub\n[14:a] failed"
+    );
+    assert_eq!(report(&[], Format::Json), "[]");
+  }
+  #[test]
+  fn formats() {
+    assert_eq!(parse_format("md"), Ok(Format::Markdown));
+    assert_eq!(parse_format(" csv "), Ok(Format::Csv));
+    assert!(parse_format("xml").is_err());
+  }
+}
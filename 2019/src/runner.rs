@@ -0,0 +1,447 @@
+use helpers::input::Resolver;
+use helpers::{Part, Registry, SolveError};
+use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Failure {
+  Solve(SolveError),
+  Input(String),
+  Timeout(Duration),
+  Panic(Panic),
+}
+impl std::fmt::Display for Failure {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Failure::Solve(err) => write!(fmt, "{}", err),
+      Failure::Input(message) => write!(fmt, "{}", message),
+      Failure::Timeout(limit) => write!(fmt, "Timed out after {:?}.", limit),
+      Failure::Panic(panic) => write!(fmt, "{}", panic),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Panic {
+  pub message: String,
+  pub location: Option<String>,
+  pub backtrace: Option<String>,
+}
+impl std::fmt::Display for Panic {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match &self.location {
+      Some(location) => write!(fmt, "Panicked at {}: {}", location, self.message),
+      None => write!(fmt, "Panicked: {}", self.message),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+  pub part: Part,
+  pub result: Result<String, Failure>,
+  pub time: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+  pub year: u32,
+  pub day: u32,
+  pub parse: Option<Duration>,
+  pub outcomes: Vec<Outcome>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+  pub jobs: usize,
+  pub timeout: Option<Duration>,
+}
+
+type Input = Arc<Box<dyn Any + Send + Sync>>;
+
+thread_local! {
+  static GUARDED: Cell<bool> = const { Cell::new(false) };
+  static CAUGHT: RefCell<Panic> = RefCell::new(Panic::default());
+}
+
+// Panics on solver threads are reported with their answer, so the hook only
+// records where they happened instead of printing them. Threads the solvers
+// start themselves, like rayon's, still go through the default hook.
+fn install_hook() {
+  static HOOK: Once = Once::new();
+  HOOK.call_once(|| {
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+      if !GUARDED.with(Cell::get) {
+        return default(info);
+      }
+      let backtrace = Backtrace::capture();
+      CAUGHT.with(|caught| {
+        *caught.borrow_mut() = Panic {
+          message: String::new(),
+          location: info.location().map(|location| location.to_string()),
+          backtrace: match backtrace.status() {
+            BacktraceStatus::Captured => Some(backtrace.to_string()),
+            _ => None,
+          },
+        }
+      });
+    }));
+  });
+}
+
+fn message(payload: &(dyn Any + Send)) -> String {
+  if let Some(message) = payload.downcast_ref::<&str>() {
+    message.to_string()
+  } else if let Some(message) = payload.downcast_ref::<String>() {
+    message.clone()
+  } else {
+    "unknown payload".into()
+  }
+}
+
+// Runs the job on its own thread so a solver that hangs or panics only loses
+// its own answer. A thread that runs past the timeout is left behind.
+fn guarded<T, F>(timeout: Option<Duration>, job: F) -> Result<(T, Duration), Failure>
+where
+  T: Send + 'static,
+  F: FnOnce() -> T + Send + 'static,
+{
+  install_hook();
+  let (sender, receiver) = mpsc::channel();
+  thread::spawn(move || {
+    GUARDED.with(|guarded| guarded.set(true));
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(job)).map_err(|payload| {
+      let mut panic = CAUGHT.with(|caught| caught.take());
+      panic.message = message(payload.as_ref());
+      Failure::Panic(panic)
+    });
+    let _ = sender.send((result, start.elapsed()));
+  });
+  let died = || {
+    Failure::Panic(Panic {
+      message: "the solver thread died".into(),
+      ..Panic::default()
+    })
+  };
+  let received = match timeout {
+    None => receiver.recv().map_err(|_| died()),
+    Some(limit) => receiver.recv_timeout(limit).map_err(|err| match err {
+      RecvTimeoutError::Timeout => Failure::Timeout(limit),
+      RecvTimeoutError::Disconnected => died(),
+    }),
+  };
+  let (result, time) = received?;
+  result.map(|value| (value, time))
+}
+
+pub fn solve(
+  registry: &Arc<Registry>,
+  year: u32,
+  day: u32,
+  parts: &[Part],
+  resolver: &Resolver,
+  timeout: Option<Duration>,
+) -> Solution {
+  let file = registry.get(year, day).and_then(|entry| entry.input);
+  match resolver.read(file) {
+    Ok(text) => solve_text(registry, year, day, parts, text, timeout),
+    Err(err) => unsolved(year, day, parts, Failure::Input(err.to_string())),
+  }
+}
+
+pub fn solve_text(
+  registry: &Arc<Registry>,
+  year: u32,
+  day: u32,
+  parts: &[Part],
+  text: String,
+  timeout: Option<Duration>,
+) -> Solution {
+  let loader = registry.clone();
+  let loaded = guarded(timeout, move || match loader.get(year, day) {
+    Some(entry) => entry.puzzle.load(&text).map_err(|err| err.to_string()),
+    None => Err(format!("{} day {} is not registered", year, day)),
+  });
+  let (input, parse): (Input, Duration) = match loaded {
+    Ok((Ok(input), parse)) => (Arc::new(input), parse),
+    Ok((Err(message), _)) => return unsolved(year, day, parts, Failure::Input(message)),
+    Err(failure) => return unsolved(year, day, parts, failure),
+  };
+  let outcomes = parts
+    .iter()
+    .map(|part| {
+      let (solver, input, part) = (registry.clone(), input.clone(), *part);
+      let answer = guarded(timeout, move || match solver.get(year, day) {
+        Some(entry) => entry.puzzle.answer(part, &**input),
+        None => Err(SolveError::NoSolution),
+      });
+      let (result, time) = match answer {
+        Ok((result, time)) => (result.map_err(Failure::Solve), time),
+        Err(failure) => (Err(failure), Duration::default()),
+      };
+      Outcome { part, result, time }
+    })
+    .collect();
+  Solution {
+    year,
+    day,
+    parse: Some(parse),
+    outcomes,
+  }
+}
+
+fn unsolved(year: u32, day: u32, parts: &[Part], failure: Failure) -> Solution {
+  let outcomes = parts
+    .iter()
+    .map(|part| Outcome {
+      part: *part,
+      result: Err(failure.clone()),
+      time: Duration::default(),
+    })
+    .collect();
+  Solution {
+    year,
+    day,
+    parse: None,
+    outcomes,
+  }
+}
+
+// Solves the days on a pool of workers, reporting them in the given order as
+// soon as every earlier day is done.
+pub fn run_all<F>(
+  registry: &Arc<Registry>,
+  year: u32,
+  days: &[u32],
+  parts: &[Part],
+  resolver: &Resolver,
+  limits: Limits,
+  mut report: F,
+) where
+  F: FnMut(Solution),
+{
+  let queue = Arc::new(Mutex::new(
+    days
+      .iter()
+      .cloned()
+      .enumerate()
+      .collect::<VecDeque<(usize, u32)>>(),
+  ));
+  let (sender, receiver) = mpsc::channel();
+  for _ in 0..limits.jobs.clamp(1, days.len().max(1)) {
+    let (queue, sender) = (queue.clone(), sender.clone());
+    let (registry, resolver, parts) = (registry.clone(), resolver.clone(), parts.to_vec());
+    thread::spawn(move || loop {
+      let job = queue.lock().map(|mut queue| queue.pop_front());
+      match job {
+        Ok(Some((idx, day))) => {
+          let solution = solve(&registry, year, day, &parts, &resolver, limits.timeout);
+          if sender.send((idx, solution)).is_err() {
+            break;
+          }
+        }
+        _ => break,
+      }
+    });
+  }
+  drop(sender);
+  let mut pending = BTreeMap::new();
+  let mut next = 0;
+  for (idx, solution) in receiver {
+    pending.insert(idx, solution);
+    while let Some(solution) = pending.remove(&next) {
+      report(solution);
+      next += 1;
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+  pub succeeded: Vec<(u32, Part)>,
+  pub failed: Vec<(u32, Part, &'static str)>,
+  pub stubs: Vec<(u32, Part)>,
+}
+
+impl Summary {
+  pub fn add(&mut self, solution: &Solution) {
+    for outcome in &solution.outcomes {
+      let key = (solution.day, outcome.part);
+      match &outcome.result {
+        Ok(_) => self.succeeded.push(key),
+        Err(Failure::Solve(SolveError::Unimplemented)) => self.stubs.push(key),
+        Err(failure) => {
+          let reason = match failure {
+            Failure::Solve(_) => "error",
+            Failure::Input(_) => "input",
+            Failure::Timeout(_) => "timeout",
+            Failure::Panic(_) => "panic",
+          };
+          self.failed.push((key.0, key.1, reason))
+        }
+      }
+    }
+  }
+}
+impl std::fmt::Display for Summary {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    let names = |keys: Vec<String>| {
+      if keys.is_empty() {
+        "-".to_owned()
+      } else {
+        keys.join(" ")
+      }
+    };
+    let plain = |keys: &[(u32, Part)]| {
+      let keys = keys.iter().map(|(day, part)| format!("{:02}{}", day, part));
+      names(keys.collect())
+    };
+    let failed = self
+      .failed
+      .iter()
+      .map(|(day, part, reason)| format!("{:02}{} ({})", day, part, reason));
+    write!(
+      fmt,
+      "succeeded: {}\nfailed:    {}\nstubs:     {}",
+      plain(&self.succeeded),
+      names(failed.collect()),
+      plain(&self.stubs)
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use helpers::{ParseError, Solver};
+  use std::fmt::Display;
+
+  struct Slow;
+  impl Solver for Slow {
+    type Input = u64;
+    fn parse(&self, text: &str) -> Result<u64, ParseError> {
+      text
+        .trim()
+        .parse()
+        .map_err(|_| ParseError::Format(format!("'{}' is not a delay", text.trim())))
+    }
+    fn part_a(&self, input: &u64) -> Result<impl Display, SolveError> {
+      thread::sleep(Duration::from_millis(*input));
+      Ok(*input)
+    }
+    fn part_b(&self, input: &u64) -> Result<impl Display, SolveError> {
+      if *input == 0 {
+        panic!("zero");
+      }
+      Ok(input * 2)
+    }
+  }
+
+  fn registry() -> Arc<Registry> {
+    let mut registry = Registry::new();
+    for day in 1..=3 {
+      registry.register(2019, day, None, Slow);
+    }
+    Arc::new(registry)
+  }
+
+  #[test]
+  fn outcomes() {
+    let registry = registry();
+    let timeout = Some(Duration::from_millis(200));
+    let parts = [Part::A, Part::B];
+    let results = |text: &str| {
+      solve_text(&registry, 2019, 1, &parts, text.into(), timeout)
+        .outcomes
+        .into_iter()
+        .map(|outcome| outcome.result)
+        .collect::<Vec<Result<String, Failure>>>()
+    };
+    assert_eq!(results("5"), vec![Ok("5".into()), Ok("10".into())]);
+    assert_eq!(
+      results("1000"),
+      vec![
+        Err(Failure::Timeout(Duration::from_millis(200))),
+        Ok("2000".into())
+      ]
+    );
+    match &results("0")[1] {
+      Err(Failure::Panic(panic)) => {
+        assert_eq!(panic.message, "zero");
+        assert!(panic
+          .location
+          .as_ref()
+          .unwrap()
+          .starts_with("src/runner.rs:"));
+      }
+      other => panic!("expected a panic, got {:?}", other),
+    }
+    assert_eq!(
+      results("x")[0],
+      Err(Failure::Input("Parse error: 'x' is not a delay.".into()))
+    );
+  }
+  #[test]
+  fn in_order() {
+    let registry = registry();
+    let mut seen = Vec::new();
+    let limits = Limits {
+      jobs: 3,
+      timeout: None,
+    };
+    run_all(
+      &registry,
+      2019,
+      &[3, 1, 2],
+      &[Part::B],
+      &Resolver::new(),
+      limits,
+      |s| seen.push(s.day),
+    );
+    assert_eq!(seen, vec![3, 1, 2]);
+  }
+  #[test]
+  fn summary() {
+    let registry = registry();
+    let mut summary = Summary::default();
+    summary.add(&solve_text(
+      &registry,
+      2019,
+      1,
+      &[Part::A, Part::B],
+      "0".into(),
+      None,
+    ));
+    summary.add(&solve_text(
+      &registry,
+      2019,
+      2,
+      &[Part::B],
+      "3".into(),
+      None,
+    ));
+    summary.add(&unsolved(
+      2019,
+      3,
+      &[Part::A],
+      Failure::Solve(SolveError::Unimplemented),
+    ));
+    assert_eq!(
+      summary.to_string(),
+      "succeeded: 01a 02b\nfailed:    01b (panic)\nstubs:     03a"
+    );
+    assert_eq!(
+      Summary::default().to_string(),
+      "succeeded: -\nfailed:    -\nstubs:     -"
+    );
+  }
+}
@@ -0,0 +1,69 @@
+use helpers::Part;
+
+pub fn parse_part(spec: &str) -> Result<Vec<Part>, String> {
+  match spec.trim() {
+    "a" | "A" => Ok(vec![Part::A]),
+    "b" | "B" => Ok(vec![Part::B]),
+    "ab" | "both" => Ok(vec![Part::A, Part::B]),
+    other => Err(format!("'{}' is not a part, use a or b", other)),
+  }
+}
+
+pub fn parse_days(spec: &str) -> Result<Vec<u32>, String> {
+  let mut days = Vec::new();
+  for item in spec.split(',').map(str::trim) {
+    let (from, to) = if let Some(pos) = item.find("..=") {
+      (day(&item[..pos])?, day(&item[pos + 3..])?)
+    } else if let Some(pos) = item.find("..") {
+      (day(&item[..pos])?, day(&item[pos + 2..])?.saturating_sub(1))
+    } else {
+      let day = day(item)?;
+      (day, day)
+    };
+    if from > to {
+      return Err(format!("'{}' is an empty range", item));
+    }
+    for day in from..=to {
+      if !days.contains(&day) {
+        days.push(day);
+      }
+    }
+  }
+  Ok(days)
+}
+
+fn day(text: &str) -> Result<u32, String> {
+  match text.trim().parse() {
+    Ok(day) if (1..=25).contains(&day) => Ok(day),
+    _ => Err(format!("'{}' is not a day between 1 and 25", text.trim())),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn days() {
+    assert_eq!(parse_days("7"), Ok(vec![7]));
+    assert_eq!(parse_days("3..7"), Ok(vec![3, 4, 5, 6]));
+    assert_eq!(parse_days("3..=7"), Ok(vec![3, 4, 5, 6, 7]));
+    assert_eq!(parse_days("9, 1..3,2"), Ok(vec![9, 1, 2]));
+    assert_eq!(
+      parse_days("0"),
+      Err("'0' is not a day between 1 and 25".to_owned())
+    );
+    assert_eq!(
+      parse_days("5..5"),
+      Err("'5..5' is an empty range".to_owned())
+    );
+    assert!(parse_days("x..3").is_err());
+  }
+  #[test]
+  fn parts() {
+    assert_eq!(parse_part("a"), Ok(vec![Part::A]));
+    assert_eq!(parse_part("B"), Ok(vec![Part::B]));
+    assert_eq!(parse_part("both"), Ok(vec![Part::A, Part::B]));
+    assert!(parse_part("c").is_err());
+  }
+}
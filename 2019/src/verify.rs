@@ -0,0 +1,189 @@
+use crate::runner::Failure;
+use helpers::answers::{escape, Answers};
+use helpers::rejected::Rejected;
+use helpers::{Part, SolveError};
+
+const WIDTH: usize = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+  Pass,
+  Fail,
+  Unknown,
+  Rejected(String),
+  Stub,
+  Error(String),
+}
+impl std::fmt::Display for Status {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Status::Pass => write!(fmt, "pass"),
+      Status::Fail => write!(fmt, "FAIL"),
+      Status::Unknown => write!(fmt, "unknown"),
+      Status::Rejected(_) => write!(fmt, "WRONG"),
+      Status::Stub => write!(fmt, "stub"),
+      Status::Error(_) => write!(fmt, "ERROR"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+  pub day: u32,
+  pub part: Part,
+  pub answer: Option<String>,
+  pub expected: Option<String>,
+  pub status: Status,
+}
+
+impl Check {
+  pub fn new(
+    answers: &Answers,
+    rejected: &Rejected,
+    year: u32,
+    day: u32,
+    part: Part,
+    result: Result<String, Failure>,
+  ) -> Check {
+    let expected = answers.get(year, day, part).map(str::to_owned);
+    let (answer, status) = match result {
+      Ok(answer) => {
+        let conflict = rejected.check(year, day, part, &answer).into_iter().next();
+        let status = match (&expected, conflict) {
+          (None, Some(conflict)) => Status::Rejected(conflict.to_string()),
+          (None, None) => Status::Unknown,
+          (Some(expected), _) if *expected == answer => Status::Pass,
+          (Some(_), _) => Status::Fail,
+        };
+        (Some(answer), status)
+      }
+      Err(Failure::Solve(SolveError::Unimplemented)) => (None, Status::Stub),
+      Err(err) => (None, Status::Error(err.to_string())),
+    };
+    Check {
+      day,
+      part,
+      answer,
+      expected,
+      status,
+    }
+  }
+  pub fn failed(&self) -> bool {
+    matches!(
+      self.status,
+      Status::Fail | Status::Rejected(_) | Status::Error(_)
+    )
+  }
+}
+
+fn cell(text: &Option<String>) -> String {
+  let text = text.as_ref().map(|text| escape(text)).unwrap_or_default();
+  if text.chars().count() > WIDTH {
+    format!("{}...", text.chars().take(WIDTH - 3).collect::<String>())
+  } else {
+    text
+  }
+}
+
+pub fn table(checks: &[Check]) -> String {
+  let mut lines = vec![format!(
+    "{:<4}{:<5}{:<8}{:<w$} {}",
+    "day",
+    "part",
+    "status",
+    "answer",
+    "expected",
+    w = WIDTH
+  )];
+  for check in checks {
+    let detail = match &check.status {
+      Status::Error(message) | Status::Rejected(message) => message.clone(),
+      Status::Fail => cell(&check.expected),
+      _ => String::new(),
+    };
+    let line = format!(
+      "{:<4}{:<5}{:<8}{:<w$} {}",
+      format!("{:02}", check.day),
+      check.part.to_string(),
+      check.status.to_string(),
+      cell(&check.answer),
+      detail,
+      w = WIDTH
+    );
+    lines.push(line.trim_end().to_owned());
+  }
+  let count = |status: Status| checks.iter().filter(|c| c.status == status).count();
+  lines.push(format!(
+    "{} passed, {} failed, {} unknown, {} stubs",
+    count(Status::Pass),
+    checks.iter().filter(|c| c.failed()).count(),
+    count(Status::Unknown),
+    count(Status::Stub)
+  ));
+  lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn verify_1() {
+    let answers = Answers::parse("2019 1 a 10\n2019 1 b 20\n2019 2 a 30\n").unwrap();
+    let rejected = Rejected::parse("2019 1 b low 21\n2019 11 a low 2008\n").unwrap();
+    let checks = vec![
+      Check::new(&answers, &rejected, 2019, 1, Part::A, Ok("10".into())),
+      Check::new(&answers, &rejected, 2019, 1, Part::B, Ok("21".into())),
+      Check::new(
+        &answers,
+        &rejected,
+        2019,
+        2,
+        Part::A,
+        Err(Failure::Solve(SolveError::Failed("oops".into()))),
+      ),
+      Check::new(&answers, &rejected, 2019, 2, Part::B, Ok("a\nb".into())),
+      Check::new(
+        &answers,
+        &rejected,
+        2019,
+        3,
+        Part::A,
+        Err(Failure::Solve(SolveError::Unimplemented)),
+      ),
+      Check::new(&answers, &rejected, 2019, 11, Part::A, Ok("2008".into())),
+      Check::new(&answers, &rejected, 2019, 11, Part::B, Ok("2008".into())),
+    ];
+    assert_eq!(
+      checks
+        .iter()
+        .map(|c| c.status.clone())
+        .collect::<Vec<Status>>(),
+      vec![
+        Status::Pass,
+        Status::Fail,
+        Status::Error("Failed: oops".into()),
+        Status::Unknown,
+        Status::Stub,
+        Status::Rejected("this answer was already rejected as too low".into()),
+        Status::Unknown
+      ]
+    );
+    assert_eq!(checks.iter().filter(|c| c.failed()).count(), 3);
+    assert_eq!(
+      table(&checks),
+      [
+        "day part status  answer                   expected",
+        "01  a    pass    10",
+        "01  b    FAIL    21                       20",
+        "02  a    ERROR                            Failed: oops",
+        "02  b    unknown a\\nb",
+        "03  a    stub",
+        "11  a    WRONG   2008                     this answer was already rejected as too low",
+        "11  b    unknown 2008",
+        "1 passed, 3 failed, 2 unknown, 1 stubs",
+      ]
+      .join("\n")
+    );
+  }
+}
@@ -55,7 +55,7 @@ pub fn solve_day03b() -> i64 {
   fewest_steps(load_wires()).unwrap()
 }
 
-fn fewest_steps(wires: Vec<Vec<Move>>) -> Option<i64> {
+pub fn fewest_steps(wires: Vec<Vec<Move>>) -> Option<i64> {
   fewest_steps_slow(wires)
 }
 
@@ -150,7 +150,7 @@ fn intersection_points(wires: Vec<Vec<Move>>) -> Vec<(Point, usize)> {
     .map(|(k, v)| (*k, v.iter().fold(0, |t, (_, s)| s + t)))
     .collect()
 }
-fn shortest_distance(wires: Vec<Vec<Move>>) -> Option<i64> {
+pub fn shortest_distance(wires: Vec<Vec<Move>>) -> Option<i64> {
   intersection_points(wires)
     .iter()
     .map(|(p, _steps)| p.0.abs() + p.1.abs())
@@ -159,18 +159,25 @@ fn shortest_distance(wires: Vec<Vec<Move>>) -> Option<i64> {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
-enum Move {
+pub enum Move {
   Right(i64),
   Left(i64),
   Up(i64),
   Down(i64),
 }
 #[derive(PartialEq, Eq, Debug)]
-enum Error {
+pub enum Error {
   BadString(String),
 }
+impl std::fmt::Display for Error {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Error::BadString(text) => write!(fmt, "'{}' is not a move", text),
+    }
+  }
+}
 impl Move {
-  fn many_from_string(text: &str) -> Result<Vec<Move>, Error> {
+  pub fn many_from_string(text: &str) -> Result<Vec<Move>, Error> {
     text.split(',').map(Move::from_string).collect()
   }
   fn from_string(text: &str) -> Result<Move, Error> {
@@ -190,10 +197,10 @@ impl Move {
 }
 
 fn load_wires() -> Vec<Vec<Move>> {
-  helpers::loader::load_as::<Vec<Move>>("./day/03/input.csv", |s| {
-    Move::many_from_string(&s).unwrap()
-  })
-  .collect::<Vec<Vec<Move>>>()
+  helpers::input::puzzle_input("day/03/input.csv")
+    .lines()
+    .map(|s| Move::many_from_string(s.trim()).unwrap())
+    .collect::<Vec<Vec<Move>>>()
 }
 
 #[cfg(test)]